@@ -1,9 +1,16 @@
 use cmake::Config;
 use division_shader_compiler_rust::{ShaderCompiler, ShaderType};
+use std::collections::HashSet;
 use std::path::{Path, PathBuf};
 use std::vec;
 use std::{env, fs};
 
+#[allow(dead_code)]
+#[path = "src/core/shader_preprocessor.rs"]
+mod shader_preprocessor;
+
+use shader_preprocessor::ShaderPreprocessor;
+
 struct DivisionBuildOptions {
     static_libs: Vec<String>,
     dynamic_libs: Vec<String>,
@@ -14,10 +21,13 @@ fn main() {
     println!("cargo:rerun-if-changed=build.rs");
     println!("cargo:rerun-if-changed=resources/*");
     println!("cargo:rerun-if-changed=division_engine_core");
+    println!("cargo:rerun-if-changed=src/core/shader_preprocessor.rs");
+
+    let division_engine_core = "division_engine_core";
+    check_core_declarations(division_engine_core);
 
     let build_options = get_build_options();
     let out_dir = env::var("OUT_DIR").unwrap();
-    let division_engine_core = "division_engine_core";
     let build_path = Path::new(&out_dir).join("build");
     let examples_dir = Path::new(&env::var("CARGO_MANIFEST_DIR").unwrap())
         .join("target")
@@ -60,6 +70,65 @@ fn main() {
     copy_resources_to_build(&examples_dir);
 }
 
+/// Functions and struct fields the bindings use that older cores don't have.
+/// Building against such a core would fail to link or, for the struct fields,
+/// silently read the structs with the wrong layout
+const REQUIRED_CORE_DECLARATIONS: &[&str] = &[
+    "division_engine_renderer_stop_loop",
+    "division_engine_renderer_begin_frame",
+    "division_engine_renderer_end_frame",
+    "division_engine_renderer_read_framebuffer",
+    "division_engine_render_pass_instance_submit",
+    "division_engine_render_pass_instance_draw_to_target",
+    "division_engine_render_pass_instance_submit_to_target",
+    "division_engine_render_target_alloc",
+    "division_engine_render_target_depth_texture",
+    "division_engine_vertex_buffer_get_descriptor",
+    "division_engine_vertex_buffer_borrow_instance_range",
+    "division_engine_vertex_buffer_return_instance_range",
+    "division_engine_texture_set_sub_data",
+    "division_engine_shader_program_last_error",
+    "division_engine_shader_program_get_reflection",
+    "window_event_callback",
+    "scroll_delta_unit",
+    "key_repeat_mask",
+    "DivisionTextInput",
+    "depth_options",
+    "stencil_options",
+    "scissor_rect",
+    "viewport_rect",
+    "mip_level_count",
+    "wrap_mode_u",
+];
+
+fn check_core_declarations(core_path: &str) {
+    let mut identifiers = HashSet::new();
+    for entry in walkdir::WalkDir::new(core_path).into_iter().flatten() {
+        let path = entry.path();
+        if path.extension().is_some_and(|e| e == "h") {
+            let header = fs::read_to_string(path).unwrap_or_default();
+            identifiers.extend(
+                header
+                    .split(|c: char| !c.is_ascii_alphanumeric() && c != '_')
+                    .map(str::to_string),
+            );
+        }
+    }
+
+    let missing: Vec<&str> = REQUIRED_CORE_DECLARATIONS
+        .iter()
+        .copied()
+        .filter(|d| !identifiers.contains(*d))
+        .collect();
+
+    if !missing.is_empty() {
+        panic!(
+            "The {core_path} headers don't declare {missing:?}. \
+             Update the {core_path} submodule to a revision that has them"
+        );
+    }
+}
+
 fn get_build_options() -> DivisionBuildOptions {
     let platform_opts = if cfg!(target_os = "macos") {
         build_with_osx_metal()
@@ -161,9 +230,8 @@ fn compile_shaders_to_msl() {
             _ => continue,
         }
 
-        let glsl_src = std::fs::read_to_string(path);
-        let glsl_src = match glsl_src {
-            Ok(v) => v,
+        let glsl_src = match ShaderPreprocessor::new().process_file(path) {
+            Ok(v) => v.source,
             Err(e) => {
                 eprintln!(
                     "Failed to read a shader file by path: `{:?}` with error: `{}` ",
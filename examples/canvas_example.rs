@@ -1,38 +1,27 @@
-/*
-   TODO: Now it works incorrectly, because screen clears for every system!
-         There is a need to make a global store for render passes,
-         rather than for each system
-*/
-
-use std::{path::Path, time::Instant};
+use std::path::Path;
 
 use division_engine_rust::{
     canvas::{
         border_radius::BorderRadius,
         color::Color32,
         decoration::Decoration,
-        rect::Rect,
+        rect::{Rect, UvRect},
         rect_renderer::RectRenderer,
         renderable_rect::RenderableRect,
         renderable_text::RenderableText,
-        renderer::{RenderQueue, Renderer},
+        renderer::{RenderQueue, Renderer, ScreenSizeUniform},
         text_renderer::TextRenderer,
     },
     core::{
-        Context, CoreRunner, DivisionId, Image, ImageSettings, LifecycleManager,
-        LifecycleManagerBuilder, TextureDescriptor, TextureFormat, input::{Keycode, MouseButton}
+        Context, CoreRunner, DivisionId, FrameTime, Image, ImageSettings,
+        LifecycleManager, LifecycleManagerBuilder, TextureDescriptor, TextureFormat,
+        TextureMinMagFilter, TextureMipmapFilter,
+        WindowEvent, input::{Keycode, MouseButton}
     },
 };
 
 use division_math::Vector2;
 
-#[repr(transparent)]
-#[derive(Clone, Copy)]
-#[allow(dead_code)]
-struct UniformData {
-    size: Vector2,
-}
-
 struct MyLifecycleManagerBuilder;
 
 struct MyLifecycleManager {
@@ -40,7 +29,6 @@ struct MyLifecycleManager {
     texts: Vec<RenderableText>,
     render_queue: RenderQueue,
     screen_size_uniform: DivisionId,
-    render_draw_time: Instant,
 
     rect_draw_system: RectRenderer,
     text_draw_system: TextRenderer,
@@ -49,11 +37,13 @@ struct MyLifecycleManager {
 }
 
 fn main() {
-    CoreRunner::new()
+    let exit_code = CoreRunner::new()
         .window_size(1024, 1024)
         .window_title("Hello rect drawer")
         .run(MyLifecycleManagerBuilder)
         .unwrap();
+
+    std::process::exit(exit_code);
 }
 
 impl LifecycleManagerBuilder for MyLifecycleManagerBuilder {
@@ -66,7 +56,15 @@ impl LifecycleManagerBuilder for MyLifecycleManagerBuilder {
                 ImageSettings::with_vertical_flip(true),
             )
             .unwrap();
-            context.create_texture_buffer_from_image(&image).unwrap()
+            let desc = TextureDescriptor::from_image(&image)
+                .unwrap()
+                .with_min_mag_filter(
+                    TextureMinMagFilter::Linear,
+                    TextureMinMagFilter::Linear,
+                )
+                .with_mipmaps(TextureMipmapFilter::Linear);
+
+            context.create_texture_buffer_from_data(&desc, image.data()).unwrap()
         };
 
         let white_texture = context
@@ -77,9 +75,11 @@ impl LifecycleManagerBuilder for MyLifecycleManagerBuilder {
             .unwrap();
 
         let screen_size_uniform = context
-            .create_uniform_buffer_with_size_of::<UniformData>()
+            .create_uniform_block_buffer::<ScreenSizeUniform>()
             .unwrap();
 
+        update_screen_size(context, screen_size_uniform, context.get_window_size());
+
         let manager = MyLifecycleManager {
             rect_draw_system: RectRenderer::new(context, screen_size_uniform),
             text_draw_system: TextRenderer::new(
@@ -93,7 +93,6 @@ impl LifecycleManagerBuilder for MyLifecycleManagerBuilder {
             screen_size_uniform,
             rects: create_rects(nevsky_texture, white_texture),
             texts: create_texts(),
-            render_draw_time: Instant::now(),
             _white_texture: white_texture,
         };
 
@@ -102,21 +101,22 @@ impl LifecycleManagerBuilder for MyLifecycleManagerBuilder {
 }
 
 impl LifecycleManager for MyLifecycleManager {
-    fn draw(&mut self, context: &mut Context) {
-        let now = Instant::now();
-        let render_time_diff = (now - self.render_draw_time).as_millis();
-        let input = context.get_input();
+    fn draw(&mut self, context: &mut Context, frame_time: &FrameTime) {
+        let render_time_diff = frame_time.delta.as_millis();
+        let input = context.input_state();
 
         let last_text = self.texts.last_mut().unwrap();
         last_text.text = format!(
             "Frame render time: {render_time_diff} ms.\
              Mouse position: x: {}, y: {}. Left button: {} Keyboard Enter state {:?} ",
-            input.mouse.pos_x, input.mouse.pos_y, 
-            input.mouse.is_button_pressed(MouseButton::Left),
-            input.keyboard.is_key_pressed(Keycode::Enter)
+            input.mouse_position().x, input.mouse_position().y,
+            input.is_button_down(MouseButton::Left),
+            input.is_key_down(Keycode::Enter)
         );
 
-        self.update_window_size(context);
+        if input.just_pressed(Keycode::Esc) {
+            context.request_exit(0);
+        }
 
         self.rect_draw_system.before_render_frame(context);
         self.text_draw_system.before_render_frame(context);
@@ -132,12 +132,10 @@ impl LifecycleManager for MyLifecycleManager {
             &mut self.render_queue,
         );
 
-        self.render_queue.draw(context);
+        self.render_queue.draw(context).unwrap();
 
         self.rect_draw_system.after_render_frame(context);
         self.text_draw_system.after_render_frame(context);
-
-        self.render_draw_time = Instant::now();
     }
 
     fn error(&mut self, _: &mut Context, _error_code: i32, message: &str) {
@@ -148,17 +146,24 @@ impl LifecycleManager for MyLifecycleManager {
         self.rect_draw_system.cleanup(context);
         self.text_draw_system.cleanup(context);
     }
-}
 
-impl MyLifecycleManager {
-    fn update_window_size(&mut self, context: &mut Context) {
-        let window_size = context.get_window_size();
-        let screen_size =
-            context.uniform_buffer_data::<UniformData>(self.screen_size_uniform);
-        screen_size.data.size = window_size;
+    fn window_event(&mut self, context: &mut Context, event: &WindowEvent) {
+        if let WindowEvent::Resized { window_size, .. } = *event {
+            update_screen_size(context, self.screen_size_uniform, window_size);
+        }
     }
 }
 
+fn update_screen_size(
+    context: &mut Context,
+    screen_size_uniform: DivisionId,
+    window_size: Vector2,
+) {
+    let screen_size =
+        context.uniform_buffer_data::<ScreenSizeUniform>(screen_size_uniform);
+    screen_size.data.size = window_size;
+}
+
 fn create_rects(
     nevsky_texture: DivisionId,
     white_texture: DivisionId,
@@ -170,6 +175,7 @@ fn create_rects(
                 color: Color32::red(),
                 border_radius: BorderRadius::all(0.),
                 texture_id: white_texture,
+                uv_rect: UvRect::full(),
             },
         ),
         RenderableRect::new(
@@ -178,6 +184,7 @@ fn create_rects(
                 color: Color32::purple(),
                 border_radius: BorderRadius::all(10.),
                 texture_id: nevsky_texture,
+                uv_rect: UvRect::full(),
             },
         ),
     ]
@@ -1,8 +1,8 @@
 use division_engine_rust::{core::{
-        Context, CoreRunner, IdWithBinding, Image, ImageSettings,
+        Context, CoreRunner, FrameTime, IdWithBinding, Image, ImageSettings,
         LifecycleManager, LifecycleManagerBuilder, RenderPassDescriptor,
         RenderPassInstance, RenderPassInstanceOwned, RenderTopology, ShaderVariableType,
-        VertexAttributeDescriptor, VertexData, VertexBufferSize,
+        VertexAttributeDescriptor, VertexData,
     }, canvas::color::Color32};
 use division_math::{Matrix4x4, Vector2, Vector3, Vector4};
 use std::path::Path;
@@ -49,7 +49,8 @@ impl LifecycleManagerBuilder for MyDelegateBuilder {
             .create_bundled_shader_program(
                 &Path::new("resources").join("shaders").join("test"),
             )
-            .unwrap();
+            .unwrap()
+            .id;
 
         let vertices_data = [
             Vert {
@@ -79,23 +80,14 @@ impl LifecycleManagerBuilder for MyDelegateBuilder {
         let indices = [0, 1, 2, 2, 3, 0];
 
         let vertex_buffer_id = context
-            .create_vertex_buffer::<Vert, Inst>(
-                VertexBufferSize {
-                    vertex_count: vertices_data.len() as u32,
-                    index_count: indices.len() as u32,
-                    instance_count: instances_data.len() as u32
-                },
+            .create_vertex_buffer_with_data(
+                &vertices_data,
+                &indices,
+                &instances_data,
                 RenderTopology::Triangles,
             )
             .unwrap();
 
-        {
-            let data = context.vertex_buffer_data::<Vert, Inst>(vertex_buffer_id);
-            data.per_vertex_data.copy_from_slice(&vertices_data);
-            data.per_instance_data.copy_from_slice(&instances_data);
-            data.vertex_indices.copy_from_slice(&indices);
-        }
-
         let texture_id = {
             let image = Image::create_bundled_image(
                 &Path::new("resources").join("images").join("nevsky.jpg"),
@@ -141,11 +133,13 @@ impl LifecycleManagerBuilder for MyDelegateBuilder {
 }
 
 impl LifecycleManager for MyDelegate {
-    fn draw(&mut self, context: &mut Context) {
-        context.draw_render_passes(
-            *Color32::white(),
-            std::slice::from_ref(&self.render_pass_instance.instance)
-        );
+    fn draw(&mut self, context: &mut Context, _: &FrameTime) {
+        context
+            .draw_render_passes(
+                *Color32::white(),
+                std::slice::from_ref(&self.render_pass_instance.instance),
+            )
+            .unwrap();
     }
 
     fn error(&mut self, _: &mut Context, _error_code: i32, message: &str) {
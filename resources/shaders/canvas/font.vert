@@ -13,17 +13,14 @@ layout (location = 0) out vec4 outColor;
 layout (location = 1) out vec2 outTexelCoord;
 layout (location = 2) out centroid vec2 outUV;
 
-layout (std140, binding = 1) uniform Uniforms {
-    vec2 screenSize;
-};
+#include "screen_space.glsl"
 
 void main() {
     vec2 vertWorldPos = vertPos * inSize + inPosition; 
-    vec2 normPos = vertWorldPos / screenSize;
     
     outColor = inColor;
     outTexelCoord = inTexelCoord + glyphInTexSize * inUV;
     outUV = inUV;
 
-    gl_Position = vec4(mix(vec2(-1,-1), vec2(1,1), normPos), 0, 1);
+    gl_Position = screenToClipPosition(vertWorldPos);
 }
\ No newline at end of file
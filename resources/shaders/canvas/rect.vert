@@ -7,6 +7,7 @@ layout (location = 2) in vec2 inSize;
 layout (location = 3) in vec2 inPosition;
 layout (location = 4) in vec4 inColor;
 layout (location = 5) in vec4 in_TRBRTLBL_BorderRadius;
+layout (location = 6) in vec4 inUVRect;
 
 layout (location = 0) out vec4 outColor;
 layout (location = 1) out vec4 out_TRBRTLBL_BorderRadius;
@@ -15,20 +16,17 @@ layout (location = 3) out vec2 outPosition;
 layout (location = 4) out vec2 outSize;
 layout (location = 5) out vec2 outVertPos;
 
-layout (std140, binding = 1) uniform Uniforms {
-    vec2 screenSize;
-};
+#include "screen_space.glsl"
 
 void main() {
     vec2 vertWorldPos = vertPos * inSize + inPosition; 
-    vec2 normPos = vertWorldPos / screenSize;
 
     outColor = inColor;
     out_TRBRTLBL_BorderRadius = in_TRBRTLBL_BorderRadius;
-    outUV = inUV;
+    outUV = mix(inUVRect.xy, inUVRect.zw, inUV);
     outPosition = inPosition;
     outSize = inSize;
     outVertPos = vertWorldPos;
 
-    gl_Position = vec4(mix(vec2(-1,-1), vec2(1,1), normPos), 0, 1);
+    gl_Position = screenToClipPosition(vertWorldPos);
 }
\ No newline at end of file
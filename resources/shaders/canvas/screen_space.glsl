@@ -0,0 +1,11 @@
+#pragma once
+
+layout (std140, binding = 1) uniform Uniforms {
+    vec2 screenSize;
+};
+
+vec4 screenToClipPosition(vec2 screenPos)
+{
+    vec2 normPos = screenPos / screenSize;
+    return vec4(mix(vec2(-1,-1), vec2(1,1), normPos), 0, 1);
+}
@@ -1,10 +1,12 @@
 use crate::core::DivisionId;
 
-use super::{color::Color32, border_radius::BorderRadius};
+use super::{border_radius::BorderRadius, color::Color32, rect::UvRect};
 
 #[derive(Clone, Copy)]
 pub struct Decoration {
     pub color: Color32,
     pub border_radius: BorderRadius,
-    pub texture_id: DivisionId
+    pub texture_id: DivisionId,
+    /// Part of the texture to draw, like a sprite of a `SpriteAtlas`
+    pub uv_rect: UvRect,
 }
\ No newline at end of file
@@ -1,7 +1,10 @@
 use division_math::Vector2;
 use std::{alloc::Layout, path::Path};
 
-use crate::core::{context, Context, DivisionId, FontGlyph, TextureDescriptor, TextureFormat};
+use crate::core::{
+    context, AtlasPacker, Context, DivisionId, FontGlyph, TextureDescriptor,
+    TextureFormat,
+};
 
 #[derive(Debug)]
 pub enum Error {
@@ -16,30 +19,32 @@ pub struct GlyphPosition {
 }
 
 #[derive(Clone, Copy)]
-struct FreeBlock {
-    position: usize,
+struct DirtyRect {
+    x: usize,
+    y: usize,
     width: usize,
+    height: usize,
 }
 
 pub struct FontTexture {
     glyphs: Vec<FontGlyph>,
     glyph_positions: Vec<GlyphPosition>,
     characters: Vec<char>,
-    rows_free_space: Vec<FreeBlock>,
+    packer: AtlasPacker,
     pixel_buffer: *mut u8,
     rasterizer_buffer: *mut u8,
     rasterizer_buffer_capacity: usize,
     width: usize,
     height: usize,
-    font_size: usize,
     font_id: DivisionId,
     texture_id: DivisionId,
-    texture_was_changed: bool,
+    dirty_rects: Vec<DirtyRect>,
 }
 
 impl FontTexture {
     pub const DEFAULT_WIDTH: usize = 1024;
     pub const DEFAULT_HEIGHT: usize = 512;
+    const GLYPH_GAP: usize = 1;
 
     pub fn new(
         context: &mut Context,
@@ -64,18 +69,17 @@ impl FontTexture {
     ) -> Result<Self, Error> {
         let font_id = context.create_font(&font_path, font_size as u32)?;
 
-        let tex_desc = TextureDescriptor::new(width, height, TextureFormat::R8Uint);
-        let texture_id = context.create_texture_buffer(&tex_desc)?;
-
         let pixel_buffer = unsafe {
             std::alloc::alloc_zeroed(Layout::from_size_align_unchecked(width * height, 1))
         };
 
-        let row_count = height / font_size;
-        let approx_char_count = row_count + (width / font_size);
+        // Glyphs are uploaded by regions, so the rest of the texture is cleared once
+        let tex_desc = TextureDescriptor::new(width, height, TextureFormat::R8Uint);
+        let texture_id = context.create_texture_buffer_from_data(&tex_desc, unsafe {
+            std::slice::from_raw_parts(pixel_buffer, width * height)
+        })?;
 
-        let mut rows_free_space = Vec::with_capacity(row_count);
-        rows_free_space.resize(row_count, FreeBlock { position: 0, width });
+        let approx_char_count = (height / font_size) + (width / font_size);
 
         Ok(FontTexture {
             glyphs: Vec::with_capacity(approx_char_count),
@@ -84,13 +88,12 @@ impl FontTexture {
             rasterizer_buffer: std::ptr::null_mut(),
             rasterizer_buffer_capacity: 0,
             pixel_buffer,
-            rows_free_space,
+            packer: AtlasPacker::new(width, height).with_padding(Self::GLYPH_GAP),
             width,
             height,
-            font_size,
             font_id,
             texture_id,
-            texture_was_changed: false,
+            dirty_rects: Vec::new(),
         })
     }
 
@@ -124,15 +127,37 @@ impl FontTexture {
         context.delete_font(self.font_id);
     }
 
+    /// Uploads the regions of the glyphs rasterized since the last upload,
+    /// or the whole texture if they cover most of it
     pub fn upload_texture(&mut self, context: &mut Context) {
-        if !self.texture_was_changed {
-            return;
-        }
+        let dirty_bytes: usize =
+            self.dirty_rects.iter().map(|r| r.width * r.height).sum();
 
-        unsafe {
-            context.set_texture_buffer_data_ptr(self.texture_id, self.pixel_buffer);
+        if dirty_bytes > self.bytes_len() / 2 {
+            unsafe {
+                context.set_texture_buffer_data_ptr(self.texture_id, self.pixel_buffer);
+            }
+        } else {
+            for rect in &self.dirty_rects {
+                let offset = rect.x + rect.y * self.width;
+                let len = (rect.height - 1) * self.width + rect.width;
+                let data = unsafe {
+                    std::slice::from_raw_parts(self.pixel_buffer.add(offset), len)
+                };
+
+                context.set_texture_buffer_sub_data(
+                    self.texture_id,
+                    rect.x as u32,
+                    rect.y as u32,
+                    rect.width as u32,
+                    rect.height as u32,
+                    self.width,
+                    data,
+                );
+            }
         }
-        self.texture_was_changed = false;
+
+        self.dirty_rects.clear();
     }
 
     pub fn cache_character(
@@ -157,7 +182,6 @@ impl FontTexture {
         character: char,
         index_to_place: usize,
     ) -> Result<(), Error> {
-        const GLYPH_GAP: usize = 1;
         let glyph = {
             let mut glyph = context.get_font_glyph(self.font_id, character)?;
             if character == ' ' {
@@ -165,30 +189,17 @@ impl FontTexture {
             }
             glyph
         };
-        let gapped_glyph_width = glyph.width as usize + GLYPH_GAP;
-        for (row, free_block) in &mut self.rows_free_space.iter_mut().enumerate() {
-            let free_after = free_block.width as isize - gapped_glyph_width as isize;
+        let rect = self
+            .packer
+            .pack(glyph.width as usize, glyph.height as usize)
+            .ok_or(Error::NoSpace)?;
 
-            if free_after < 0 {
-                continue;
-            }
+        self.glyphs.insert(index_to_place, glyph);
+        self.glyph_positions
+            .insert(index_to_place, GlyphPosition { x: rect.x, y: rect.y });
+        self.characters.insert(index_to_place, character);
 
-            let position = GlyphPosition {
-                x: free_block.position,
-                y: self.font_size * row,
-            };
-
-            free_block.position += gapped_glyph_width;
-            free_block.width = free_after as usize;
-
-            self.glyphs.insert(index_to_place, glyph);
-            self.glyph_positions.insert(index_to_place, position);
-            self.characters.insert(index_to_place, character);
-
-            return Ok(());
-        }
-
-        Err(Error::NoSpace)
+        Ok(())
     }
 
     fn rasterize_glyph(
@@ -240,10 +251,33 @@ impl FontTexture {
             }
         }
 
-        self.texture_was_changed = true;
+        self.mark_dirty(DirtyRect {
+            x: position.x,
+            y: position.y,
+            width: glyph.width as usize,
+            height: glyph.height as usize,
+        });
 
         Ok(())
     }
+
+    /// Glyphs packed at the same height are merged into a single rect,
+    /// as they are mostly placed one after another
+    fn mark_dirty(&mut self, rect: DirtyRect) {
+        if rect.width == 0 || rect.height == 0 {
+            return;
+        }
+
+        match self.dirty_rects.iter_mut().find(|r| r.y == rect.y) {
+            Some(row_rect) => {
+                let right = (row_rect.x + row_rect.width).max(rect.x + rect.width);
+                row_rect.x = row_rect.x.min(rect.x);
+                row_rect.width = right - row_rect.x;
+                row_rect.height = row_rect.height.max(rect.height);
+            }
+            None => self.dirty_rects.push(rect),
+        }
+    }
 }
 
 impl Drop for FontTexture {
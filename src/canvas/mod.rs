@@ -7,4 +7,5 @@ pub mod rect_renderer;
 pub mod renderable_rect;
 pub mod renderable_text;
 pub mod renderer;
+pub mod sprite_atlas;
 pub mod text_renderer;
\ No newline at end of file
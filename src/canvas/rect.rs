@@ -1,5 +1,8 @@
-use division_math::Vector2;
+use division_math::{Vector2, Vector4};
 
+use crate::core::PixelRect;
+
+#[derive(Clone, Copy, Debug)]
 pub struct Rect {
     pub center: Vector2,
     pub extents: Vector2, // half size
@@ -78,4 +81,92 @@ impl Rect {
             self.center.y - self.extents.y,
         )
     }
+
+    pub fn intersects(&self, other: &Rect) -> bool {
+        let distance = self.center - other.center;
+        let extents = self.extents + other.extents;
+
+        distance.x.abs() < extents.x && distance.y.abs() < extents.y
+    }
+
+    /// Returns the overlapping part of the rects, or an empty rect
+    /// if they don't overlap
+    pub fn intersection(&self, other: &Rect) -> Rect {
+        let bottom_left = Vector2::new(
+            self.bottom_left().x.max(other.bottom_left().x),
+            self.bottom_left().y.max(other.bottom_left().y),
+        );
+        let top_right = Vector2::new(
+            self.top_right().x.min(other.top_right().x),
+            self.top_right().y.min(other.top_right().y),
+        );
+        let size = Vector2::new(
+            (top_right.x - bottom_left.x).max(0.),
+            (top_right.y - bottom_left.y).max(0.),
+        );
+
+        Rect::from_bottom_left(bottom_left, size)
+    }
+
+    /// Smallest pixel rect that covers this one
+    pub fn to_pixel_rect(&self) -> PixelRect {
+        let bottom_left = self.bottom_left();
+        let top_right = self.top_right();
+        let x = bottom_left.x.floor() as i32;
+        let y = bottom_left.y.floor() as i32;
+
+        PixelRect::new(
+            x,
+            y,
+            (top_right.x.ceil() as i32 - x).max(0) as u32,
+            (top_right.y.ceil() as i32 - y).max(0) as u32,
+        )
+    }
+}
+
+/// Rect of a texture in UV coordinates, from `min` at the bottom left
+/// to `max` at the top right
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct UvRect {
+    pub min: Vector2,
+    pub max: Vector2,
+}
+
+impl UvRect {
+    /// The whole texture
+    pub fn full() -> UvRect {
+        UvRect {
+            min: Vector2::new(0., 0.),
+            max: Vector2::new(1., 1.),
+        }
+    }
+
+    /// UVs of the `x`, `y`, `width`, `height` pixel rect of a texture
+    pub fn from_pixels(
+        x: usize,
+        y: usize,
+        width: usize,
+        height: usize,
+        texture_size: Vector2,
+    ) -> UvRect {
+        UvRect {
+            min: Vector2::new(x as f32 / texture_size.x, y as f32 / texture_size.y),
+            max: Vector2::new(
+                (x + width) as f32 / texture_size.x,
+                (y + height) as f32 / texture_size.y,
+            ),
+        }
+    }
+}
+
+impl Default for UvRect {
+    fn default() -> Self {
+        UvRect::full()
+    }
+}
+
+impl From<UvRect> for Vector4 {
+    fn from(value: UvRect) -> Self {
+        Vector4::new(value.min.x, value.min.y, value.max.x, value.max.y)
+    }
 }
@@ -1,11 +1,11 @@
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 use division_math::{Vector2, Vector4};
 
 use crate::core::{
     AlphaBlend, AlphaBlendOperation, Context, DivisionId, IdWithBinding,
     RenderPassDescriptor, RenderPassInstance, RenderTopology, ShaderVariableType,
-    VertexAttributeDescriptor, VertexBufferData, VertexBufferSize, VertexData,
+    StreamingInstanceBuffer, VertexAttributeDescriptor, VertexData,
 };
 
 use super::{
@@ -14,13 +14,11 @@ use super::{
 };
 
 pub struct RectRenderer {
-    shader_id: DivisionId,
-    vertex_buffer_id: DivisionId,
-    render_pass_descriptor: DivisionId,
+    /// `None` if the shader was passed to `with_shader`, its owner deletes it
+    owned_shader_id: Option<DivisionId>,
+    instances: StreamingInstanceBuffer<RectVertexData, RectInstanceData>,
     screen_size_uniform: IdWithBinding,
     textures_heap: Vec<IdWithBinding>,
-    instance_count: u32,
-    instance_capacity: u32,
 }
 
 #[repr(C, packed)]
@@ -43,6 +41,8 @@ struct RectInstanceData {
     color: Vector4,
     #[location(5)]
     trbl_border_radius: Vector4,
+    #[location(6)]
+    uv_rect: Vector4,
 }
 
 pub const SCREEN_SIZE_UNIFORM_LOCATION: u32 = 1;
@@ -52,6 +52,8 @@ pub const DEFAULT_RECT_CAPACITY: u32 = 64;
 pub const VERTEX_PER_RECT: u32 = 4;
 pub const INDEX_PER_RECT: u32 = 6;
 
+const RECT_INDICES: [u32; INDEX_PER_RECT as usize] = [0, 1, 2, 2, 3, 0];
+
 impl RectRenderer {
     pub fn new(
         context: &mut Context,
@@ -70,20 +72,37 @@ impl RectRenderer {
         rect_capacity: u32,
     ) -> RectRenderer {
         let shader_id = context
-            .create_bundled_shader_program(
-                &Path::new("resources")
-                    .join("shaders")
-                    .join("canvas")
-                    .join("rect"),
-            )
-            .unwrap();
-
-        let vertex_buffer_id = make_vertex_buffer(context, rect_capacity);
-        generate_rect_drawer_vertex_data(context, vertex_buffer_id);
-
-        let render_pass_descriptor = context
-            .create_render_pass_descriptor(
-                &RenderPassDescriptor::with_shader_and_vertex_buffer(
+            .create_bundled_shader_program(&RectRenderer::shader_path())
+            .unwrap()
+            .id;
+
+        let mut renderer = RectRenderer::with_shader(
+            context,
+            screen_size_uniform_id,
+            rect_capacity,
+            shader_id,
+        );
+        renderer.owned_shader_id = Some(shader_id);
+        renderer
+    }
+
+    /// Draws with a shader owned by the caller, e.g. by `ShaderHotReloader`.
+    /// The shader must be compatible with the bundled one, and it isn't deleted
+    /// by `cleanup`
+    pub fn with_shader(
+        context: &mut Context,
+        screen_size_uniform_id: DivisionId,
+        rect_capacity: u32,
+        shader_id: DivisionId,
+    ) -> RectRenderer {
+        let instances = StreamingInstanceBuffer::new(
+            context,
+            &rect_vertices(),
+            &RECT_INDICES,
+            RenderTopology::Triangles,
+            rect_capacity as usize,
+            |vertex_buffer_id| {
+                RenderPassDescriptor::with_shader_and_vertex_buffer(
                     shader_id,
                     vertex_buffer_id,
                 )
@@ -91,29 +110,40 @@ impl RectRenderer {
                     AlphaBlend::SrcAlpha,
                     AlphaBlend::OneMinusSrcAlpha,
                     AlphaBlendOperation::Add,
-                ),
-            )
-            .unwrap();
+                )
+            },
+        )
+        .unwrap();
 
         RectRenderer {
-            shader_id,
+            owned_shader_id: None,
             screen_size_uniform: IdWithBinding {
                 id: screen_size_uniform_id,
                 shader_binding: SCREEN_SIZE_UNIFORM_LOCATION,
             },
-            render_pass_descriptor,
-            vertex_buffer_id,
+            instances,
             textures_heap: Vec::new(),
-            instance_count: 0,
-            instance_capacity: rect_capacity,
         }
     }
 
+    /// Bundled shader path, as passed to `create_bundled_shader_program`
+    pub fn shader_path() -> PathBuf {
+        Path::new("resources")
+            .join("shaders")
+            .join("canvas")
+            .join("rect")
+    }
+
+    /// Render pass descriptors that use the shader, one per streaming slot
+    pub fn render_pass_descriptors(&self) -> impl Iterator<Item = DivisionId> + '_ {
+        self.instances.render_pass_descriptors()
+    }
+
     fn create_new_pass(&mut self, texture_id: DivisionId) -> RenderPassInstance {
-        let mut pass = RenderPassInstance::new(self.render_pass_descriptor)
+        let mut pass = RenderPassInstance::new(self.instances.render_pass_descriptor())
             .vertices(VERTEX_PER_RECT, INDEX_PER_RECT)
             .enable_instancing();
-        pass.first_instance = self.instance_count as u32;
+        pass.first_instance = self.instances.instance_count();
 
         self.textures_heap
             .push(IdWithBinding::new(texture_id, TEXTURE_SHADER_LOCATION));
@@ -131,9 +161,10 @@ impl RectRenderer {
     }
 
     pub fn cleanup(&mut self, context: &mut Context) {
-        context.delete_shader_program(self.shader_id);
-        context.delete_render_pass_descriptor(self.render_pass_descriptor);
-        context.delete_vertex_buffer(self.vertex_buffer_id);
+        if let Some(shader_id) = self.owned_shader_id.take() {
+            context.delete_shader_program(shader_id);
+        }
+        self.instances.delete(context);
     }
 }
 
@@ -141,7 +172,7 @@ impl Renderer for RectRenderer {
     type RenderableData = RenderableRect;
 
     fn before_render_frame(&mut self, _: &mut Context) {
-        self.instance_count = 0;
+        self.instances.begin_frame();
         self.textures_heap.clear();
     }
 
@@ -158,50 +189,44 @@ impl Renderer for RectRenderer {
         let mut curr_pass_tex = renderables[0].decoration.texture_id;
         let mut pass = self.create_new_pass(curr_pass_tex);
 
-        let renderables_len = renderables.len() as u32;
-        if self.instance_count + renderables_len >= self.instance_capacity {
-            context.vertex_buffer_resize(
-                self.vertex_buffer_id,
-                VertexBufferSize {
-                    vertex_count: VERTEX_PER_RECT as u32,
-                    index_count: INDEX_PER_RECT as u32,
-                    instance_count: (std::cmp::max(1, self.instance_capacity) * 2) as u32,
-                },
-            )
-        }
-
-        let vertex_buffer_data = get_vertex_buffer_data(context, self.vertex_buffer_id);
-
         for r in renderables {
+            if render_queue.clip_rect().is_some_and(|c| !c.intersects(&r.rect)) {
+                continue;
+            }
+
             let renderable_texture_id = r.decoration.texture_id;
 
             if r.decoration.texture_id != curr_pass_tex {
-                render_queue.enqueue_render_pass(pass);
+                if pass.instance_count > 0 {
+                    render_queue.enqueue_render_pass(pass);
+                }
                 pass = self.create_new_pass(r.decoration.texture_id);
                 curr_pass_tex = renderable_texture_id;
             }
 
-            let d =
-                &mut vertex_buffer_data.per_instance_data[self.instance_count as usize];
-            d.position = r.rect.bottom_left();
-            d.size = r.rect.size();
-            d.color = *r.decoration.color;
-            d.trbl_border_radius = *r.decoration.border_radius;
-
+            self.instances.push(RectInstanceData {
+                position: r.rect.bottom_left(),
+                size: r.rect.size(),
+                color: *r.decoration.color,
+                trbl_border_radius: *r.decoration.border_radius,
+                uv_rect: r.decoration.uv_rect.into(),
+            });
             pass.instance_count += 1;
-            self.instance_count += 1;
         }
 
-        render_queue.enqueue_render_pass(pass);
+        self.instances.flush(context).unwrap();
+
+        // Everything could be culled by the clip rect
+        if pass.instance_count > 0 {
+            render_queue.enqueue_render_pass(pass);
+        }
     }
 
     fn after_render_frame(&mut self, _: &mut Context) {}
 }
 
-fn generate_rect_drawer_vertex_data(context: &mut Context, vertex_buffer_id: DivisionId) {
-    let data = get_vertex_buffer_data(context, vertex_buffer_id);
-
-    let vertex_data = [
+fn rect_vertices() -> [RectVertexData; VERTEX_PER_RECT as usize] {
+    [
         RectVertexData {
             vert_pos: Vector2::new(0., 1.),
             uv: Vector2::new(0., 1.),
@@ -218,30 +243,5 @@ fn generate_rect_drawer_vertex_data(context: &mut Context, vertex_buffer_id: Div
             vert_pos: Vector2::new(1., 1.),
             uv: Vector2::new(1., 1.),
         },
-    ];
-    let indices = [0, 1, 2, 2, 3, 0];
-
-    data.vertex_indices.copy_from_slice(&indices);
-    data.per_vertex_data.copy_from_slice(&vertex_data);
-}
-
-fn make_vertex_buffer(context: &mut Context, capacity: u32) -> DivisionId {
-    context
-        .create_vertex_buffer::<RectVertexData, RectInstanceData>(
-            VertexBufferSize {
-                vertex_count: VERTEX_PER_RECT,
-                index_count: INDEX_PER_RECT,
-                instance_count: capacity,
-            },
-            RenderTopology::Triangles,
-        )
-        .unwrap()
-}
-
-#[inline(always)]
-fn get_vertex_buffer_data(
-    context: &mut Context,
-    vertex_buffer_id: DivisionId,
-) -> VertexBufferData<RectVertexData, RectInstanceData> {
-    context.vertex_buffer_data(vertex_buffer_id)
+    ]
 }
@@ -1,10 +1,27 @@
-use crate::core::{Context, RenderPassInstance};
+use division_math::Vector2;
 
-use super::color::Color32;
+use crate::core::{
+    context::Error, Context, DivisionId, LoadAction, RenderPassInstance,
+    RenderPassIsntanceCapabilityMask, UniformBlock, UniformBlockLayout,
+};
+
+use super::{color::Color32, rect::Rect};
 
 pub struct RenderQueue {
     pub clear_color: Color32,
     pub data: Vec<RenderPassInstance>,
+    clip_rects: Vec<Rect>,
+    /// Indices of the enqueued passes with their clip rects in canvas units,
+    /// converted to scissor rects when the passes are submitted
+    clipped_passes: Vec<(usize, Rect)>,
+}
+
+/// The `Uniforms` block of the canvas shaders
+#[repr(C)]
+#[derive(UniformBlock, Clone, Copy)]
+pub struct ScreenSizeUniform {
+    #[uniform_block(name = "screenSize")]
+    pub size: Vector2,
 }
 
 pub trait Renderer {
@@ -22,15 +39,106 @@ pub trait Renderer {
 
 impl RenderQueue {
     pub fn new(clear_color: Color32) -> RenderQueue {
-        RenderQueue { data: Vec::new(), clear_color }
+        RenderQueue {
+            data: Vec::new(),
+            clear_color,
+            clip_rects: Vec::new(),
+            clipped_passes: Vec::new(),
+        }
+    }
+
+    /// Passes enqueued until the matching `pop_clip_rect` are clipped by the rect,
+    /// intersected with the already pushed ones
+    pub fn push_clip_rect(&mut self, rect: Rect) {
+        let rect = match self.clip_rect() {
+            Some(clip_rect) => clip_rect.intersection(&rect),
+            None => rect,
+        };
+        self.clip_rects.push(rect);
+    }
+
+    pub fn pop_clip_rect(&mut self) -> Option<Rect> {
+        self.clip_rects.pop()
     }
 
+    /// The current clip rect, or `None` if nothing is clipped
+    #[inline]
+    pub fn clip_rect(&self) -> Option<&Rect> {
+        self.clip_rects.last()
+    }
+
+    /// Enqueues the pass, clipping it by the current clip rect
+    /// unless it has its own scissor
     pub fn enqueue_render_pass(&mut self, render_pass: RenderPassInstance) {
+        let has_scissor = render_pass
+            .capabilities_mask
+            .contains(RenderPassIsntanceCapabilityMask::Scissor);
+
+        if let Some(clip_rect) = self.clip_rect().filter(|_| !has_scissor) {
+            self.clipped_passes.push((self.data.len(), *clip_rect));
+        }
+
         self.data.push(render_pass);
     }
 
-    pub fn draw(&mut self, context: &mut Context) {
-        context.draw_render_passes(*self.clear_color, &self.data);
+    /// Draws a whole frame cleared with the `clear_color`
+    pub fn draw(&mut self, context: &mut Context) -> Result<(), Error> {
+        context.begin_frame();
+        let result = self.submit(context, LoadAction::Clear(*self.clear_color));
+        context.end_frame();
+
+        result
+    }
+
+    /// Submits the enqueued passes into the current frame,
+    /// see `Context::submit_render_passes`. The queue is emptied even if it fails
+    pub fn submit(
+        &mut self,
+        context: &mut Context,
+        load_action: LoadAction,
+    ) -> Result<(), Error> {
+        self.apply_clip_rects(context.framebuffer_scale());
+        let result = context.submit_render_passes(load_action, &self.data);
         self.data.clear();
+
+        result
     }
-}
\ No newline at end of file
+
+    pub fn draw_to_target(
+        &mut self,
+        context: &mut Context,
+        render_target_id: DivisionId,
+    ) -> Result<(), Error> {
+        // Render targets are sized in canvas units
+        self.apply_clip_rects(Vector2::new(1., 1.));
+        let result = context.draw_render_passes_to_target(
+            render_target_id,
+            *self.clear_color,
+            &self.data,
+        );
+        self.data.clear();
+
+        result
+    }
+
+    /// Canvas units are window units, the scissor is in framebuffer pixels
+    fn apply_clip_rects(&mut self, framebuffer_scale: Vector2) {
+        for (index, clip_rect) in self.clipped_passes.drain(..) {
+            let Some(render_pass) = self.data.get_mut(index) else {
+                continue;
+            };
+
+            let scaled = Rect {
+                center: Vector2::new(
+                    clip_rect.center.x * framebuffer_scale.x,
+                    clip_rect.center.y * framebuffer_scale.y,
+                ),
+                extents: Vector2::new(
+                    clip_rect.extents.x * framebuffer_scale.x,
+                    clip_rect.extents.y * framebuffer_scale.y,
+                ),
+            };
+            *render_pass = render_pass.clone().scissor(scaled.to_pixel_rect());
+        }
+    }
+}
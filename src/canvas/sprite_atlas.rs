@@ -0,0 +1,131 @@
+use division_math::Vector2;
+
+use crate::core::{
+    context, texture::channels_to_default_swizzle, AtlasPacker, Context, DivisionId,
+    Image, TextureDescriptor, TextureFormat,
+};
+
+use super::rect::UvRect;
+
+#[derive(Debug)]
+pub enum Error {
+    Context(context::Error),
+    NoSpace,
+    ChannelCountMismatch { expected: usize, actual: usize },
+    /// Images can't be uploaded to the format, see `image_channel_count`
+    UnsupportedFormat(TextureFormat),
+}
+
+/// Texture with many images packed at runtime, so rects with different images
+/// can be drawn with the same texture in one render pass
+pub struct SpriteAtlas {
+    packer: AtlasPacker,
+    texture_id: DivisionId,
+    format: TextureFormat,
+}
+
+impl SpriteAtlas {
+    pub const DEFAULT_SIZE: usize = 1024;
+    pub const DEFAULT_PADDING: usize = 1;
+
+    pub fn new(context: &mut Context, format: TextureFormat) -> Result<Self, Error> {
+        Self::with_resolution(context, format, Self::DEFAULT_SIZE, Self::DEFAULT_SIZE)
+    }
+
+    /// Fails with `UnsupportedFormat` if the format isn't 8 bits per channel
+    /// in RGBA order, e.g. for BGRA, float and depth formats
+    pub fn with_resolution(
+        context: &mut Context,
+        format: TextureFormat,
+        width: usize,
+        height: usize,
+    ) -> Result<Self, Error> {
+        let Some(channels) = format.image_channel_count() else {
+            return Err(Error::UnsupportedFormat(format));
+        };
+
+        // Grayscale and RGB images are sampled as RGBA, as with `from_image`
+        let tex_desc = TextureDescriptor::new(width, height, format);
+        let tex_desc = match channels_to_default_swizzle(channels) {
+            Some(swizzle) => tex_desc.with_channels_swizzle(swizzle),
+            None => tex_desc,
+        };
+        let texture_id = context.create_texture_buffer_from_data(
+            &tex_desc,
+            &vec![0; width * height * format.bytes_per_pixel()],
+        )?;
+
+        Ok(SpriteAtlas {
+            packer: AtlasPacker::new(width, height).with_padding(Self::DEFAULT_PADDING),
+            texture_id,
+            format,
+        })
+    }
+
+    #[inline]
+    pub fn texture_id(&self) -> DivisionId {
+        self.texture_id
+    }
+
+    #[inline]
+    pub fn size(&self) -> Vector2 {
+        Vector2::new(self.packer.width() as f32, self.packer.height() as f32)
+    }
+
+    /// Packs the image and uploads it to the texture.
+    /// The image must have as many channels as the atlas format
+    pub fn add_image(
+        &mut self,
+        context: &mut Context,
+        image: &Image,
+    ) -> Result<UvRect, Error> {
+        let expected = self
+            .format
+            .image_channel_count()
+            .expect("The format is checked on creation");
+        if image.channels() != expected {
+            return Err(Error::ChannelCountMismatch {
+                expected,
+                actual: image.channels(),
+            });
+        }
+
+        let rect = self
+            .packer
+            .pack(image.width(), image.height())
+            .ok_or(Error::NoSpace)?;
+
+        context.set_texture_buffer_sub_data(
+            self.texture_id,
+            rect.x as u32,
+            rect.y as u32,
+            rect.width as u32,
+            rect.height as u32,
+            image.row_stride(),
+            image.data(),
+        );
+
+        Ok(UvRect::from_pixels(
+            rect.x,
+            rect.y,
+            rect.width,
+            rect.height,
+            self.size(),
+        ))
+    }
+
+    /// Forgets the packed images, the next ones overwrite them
+    pub fn clear(&mut self) {
+        self.packer.clear();
+    }
+
+    pub fn delete(&mut self, context: &mut Context) {
+        context.delete_texture_buffer(self.texture_id);
+    }
+}
+
+impl From<context::Error> for Error {
+    fn from(value: context::Error) -> Self {
+        Error::Context(value)
+    }
+}
@@ -1,4 +1,4 @@
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 use division_engine_rust_macro::location;
 use division_math::{Vector2, Vector4};
@@ -6,10 +6,11 @@ use division_math::{Vector2, Vector4};
 use crate::core::{
     AlphaBlend, AlphaBlendOperation, Context, DivisionId, IdWithBinding,
     RenderPassDescriptor, RenderPassInstance, RenderTopology, ShaderVariableType,
-    VertexAttributeDescriptor, VertexBufferSize, VertexData,
+    StreamingInstanceBuffer, VertexAttributeDescriptor, VertexData,
 };
 
 use super::{
+    rect::Rect,
     renderable_text::RenderableText,
     renderer::{RenderQueue, Renderer}, font_texture::FontTexture,
 };
@@ -18,16 +19,9 @@ pub struct TextRenderer {
     font_texture: FontTexture,
     screen_size_uniform: IdWithBinding,
     textures_heap: Vec<IdWithBinding>,
-    vertex_buffer_id: u32,
-    render_pass_desc_id: u32,
-    instance_count: u32,
-    instance_capacity: u32,
-}
-
-#[repr(C, packed)]
-#[derive(Clone, Copy)]
-struct UniformData {
-    pub size: Vector2,
+    /// `None` if the shader was passed to `with_shader`, its owner deletes it
+    owned_shader_id: Option<DivisionId>,
+    instances: StreamingInstanceBuffer<TextVertex, TextInstance>,
 }
 
 #[repr(C, packed)]
@@ -84,34 +78,43 @@ impl TextRenderer {
         screen_size_uniform_id: DivisionId,
         font_path: &Path,
         characters_capacity: u32,
+    ) -> TextRenderer {
+        let shader_id = context
+            .create_bundled_shader_program(&TextRenderer::shader_path())
+            .unwrap()
+            .id;
+
+        let mut renderer = TextRenderer::with_shader(
+            context,
+            screen_size_uniform_id,
+            font_path,
+            characters_capacity,
+            shader_id,
+        );
+        renderer.owned_shader_id = Some(shader_id);
+        renderer
+    }
+
+    /// Draws with a shader owned by the caller, e.g. by `ShaderHotReloader`.
+    /// The shader must be compatible with the bundled one, and it isn't deleted
+    /// by `cleanup`
+    pub fn with_shader(
+        context: &mut Context,
+        screen_size_uniform_id: DivisionId,
+        font_path: &Path,
+        characters_capacity: u32,
+        shader_id: DivisionId,
     ) -> TextRenderer {
         let font_texture =
             FontTexture::new(context, font_path, RASTERIZED_FONT_SIZE).unwrap();
-        let shader_id = context
-            .create_bundled_shader_program(
-                &Path::new("resources")
-                    .join("shaders")
-                    .join("canvas")
-                    .join("font"),
-            )
-            .unwrap();
-
-        let vertex_buffer_id = context
-            .create_vertex_buffer::<TextVertex, TextInstance>(
-                VertexBufferSize {
-                    vertex_count: VERTEX_PER_RECT,
-                    index_count: INDEX_PER_RECT,
-                    instance_count: characters_capacity,
-                },
-                RenderTopology::Triangles,
-            )
-            .unwrap();
-
-        fill_vertex_data(context, vertex_buffer_id);
-
-        let render_pass_desc_id = context
-            .create_render_pass_descriptor(
-                &RenderPassDescriptor::with_shader_and_vertex_buffer(
+        let instances = StreamingInstanceBuffer::new(
+            context,
+            &text_vertices(),
+            &[0, 1, 2, 2, 3, 0],
+            RenderTopology::Triangles,
+            characters_capacity as usize,
+            |vertex_buffer_id| {
+                RenderPassDescriptor::with_shader_and_vertex_buffer(
                     shader_id,
                     vertex_buffer_id,
                 )
@@ -119,60 +122,53 @@ impl TextRenderer {
                     AlphaBlend::SrcAlpha,
                     AlphaBlend::OneMinusSrcAlpha,
                     AlphaBlendOperation::Add,
-                ),
-            )
-            .unwrap();
+                )
+            },
+        )
+        .unwrap();
 
         TextRenderer {
             font_texture,
-            vertex_buffer_id,
             screen_size_uniform: IdWithBinding::new(
                 screen_size_uniform_id,
                 SCREEN_SIZE_UNIFORM_LOCATION,
             ),
             textures_heap: Vec::new(),
-            instance_count: 0,
-            render_pass_desc_id,
-            instance_capacity: characters_capacity,
+            owned_shader_id: None,
+            instances,
         }
     }
 
+    /// Bundled shader path, as passed to `create_bundled_shader_program`
+    pub fn shader_path() -> PathBuf {
+        Path::new("resources")
+            .join("shaders")
+            .join("canvas")
+            .join("font")
+    }
+
+    /// Render pass descriptors that use the shader, one per streaming slot
+    pub fn render_pass_descriptors(&self) -> impl Iterator<Item = DivisionId> + '_ {
+        self.instances.render_pass_descriptors()
+    }
+
     fn add_text_to_pass(
         &mut self,
         context: &mut Context,
         render_pass_instance: &mut RenderPassInstance,
         renderable: &RenderableText,
+        clip_rect: Option<Rect>,
     ) {
         let font_atlas_size = self.font_texture.size();
-        let base_instance = render_pass_instance.instance_count as usize;
         let font_scale = renderable.font_size / RASTERIZED_FONT_SIZE as f32;
 
-        let char_count = {
-            let mut char_count = 0;
-            for ch in renderable.text.chars() {
-                self.font_texture.cache_character(context, ch).unwrap();
-                char_count += 1;
-            }
-            char_count
-        };
-
-        if self.instance_count + char_count > self.instance_capacity {
-            context.vertex_buffer_resize(
-                self.vertex_buffer_id,
-                VertexBufferSize {
-                    vertex_count: VERTEX_PER_RECT,
-                    index_count: INDEX_PER_RECT,
-                    instance_count: std::cmp::max(1, self.instance_capacity * 2) as u32,
-                },
-            );
+        for ch in renderable.text.chars() {
+            self.font_texture.cache_character(context, ch).unwrap();
         }
 
-        let data =
-            context.vertex_buffer_data::<TextVertex, TextInstance>(self.vertex_buffer_id);
-
         let mut pen_pos = renderable.position;
 
-        for (i, ch) in renderable.text.chars().enumerate() {
+        for ch in renderable.text.chars() {
             let (glyph, pos) = self.font_texture.find_glyph_layout(ch).unwrap();
             let scaled_advance = glyph.advance_x as f32 * font_scale;
 
@@ -183,32 +179,40 @@ impl TextRenderer {
                     glyph.left as f32 * font_scale,
                     (glyph.top as f32 - glyph.height as f32) * font_scale,
                 );
+                let glyph_rect = Rect::from_bottom_left(
+                    pen_pos + offset,
+                    Vector2::new(scaled_width, scaled_height),
+                );
+
+                if clip_rect.is_some_and(|c| !c.intersects(&glyph_rect)) {
+                    pen_pos.x += scaled_advance;
+                    continue;
+                }
 
-                data.per_instance_data[base_instance + i] = TextInstance {
+                self.instances.push(TextInstance {
                     texel_coord: Vector2::new(pos.x as f32, pos.y as f32),
-                    size: Vector2::new(scaled_width, scaled_height),
-                    position: pen_pos + offset,
+                    size: glyph_rect.size(),
+                    position: glyph_rect.bottom_left(),
                     color: *renderable.color,
                     glyph_in_tex_size: Vector2::new(
                         glyph.width as f32,
                         glyph.height as f32,
                     ),
                     tex_size: font_atlas_size,
-                };
+                });
+                render_pass_instance.instance_count += 1;
             }
 
             pen_pos.x += scaled_advance as f32;
-
-            render_pass_instance.instance_count += 1;
-            self.instance_count += 1;
         }
     }
 
     pub fn cleanup(&mut self, context: &mut Context) {
-        context.delete_render_pass_descriptor(self.render_pass_desc_id);
-
+        if let Some(shader_id) = self.owned_shader_id.take() {
+            context.delete_shader_program(shader_id);
+        }
+        self.instances.delete(context);
         self.font_texture.delete(context);
-        context.delete_vertex_buffer(self.vertex_buffer_id);
     }
 }
 
@@ -216,7 +220,7 @@ impl<'a> Renderer for TextRenderer {
     type RenderableData = RenderableText;
 
     fn before_render_frame(&mut self, _: &mut Context) {
-        self.instance_count = 0;
+        self.instances.begin_frame();
         self.textures_heap.clear();
     }
 
@@ -230,9 +234,11 @@ impl<'a> Renderer for TextRenderer {
             return;
         }
 
-        let mut render_pass = RenderPassInstance::new(self.render_pass_desc_id)
-            .vertices(VERTEX_PER_RECT, INDEX_PER_RECT)
-            .enable_instancing();
+        let mut render_pass =
+            RenderPassInstance::new(self.instances.render_pass_descriptor())
+                .vertices(VERTEX_PER_RECT, INDEX_PER_RECT)
+                .enable_instancing();
+        render_pass.first_instance = self.instances.instance_count();
 
         unsafe {
             self.textures_heap.push(IdWithBinding::new(
@@ -246,22 +252,24 @@ impl<'a> Renderer for TextRenderer {
             );
         }
 
+        let clip_rect = render_queue.clip_rect().copied();
         for renderable in data {
-            self.add_text_to_pass(context, &mut render_pass, renderable)
+            self.add_text_to_pass(context, &mut render_pass, renderable, clip_rect)
         }
 
-        render_queue.enqueue_render_pass(render_pass);
+        if render_pass.instance_count > 0 {
+            render_queue.enqueue_render_pass(render_pass);
+        }
 
+        self.instances.flush(context).unwrap();
         self.font_texture.upload_texture(context);
     }
 
     fn after_render_frame(&mut self, _: &mut Context) {}
 }
 
-fn fill_vertex_data(context: &mut Context, vertex_buffer_id: DivisionId) {
-    let vb_data =
-        context.vertex_buffer_data::<TextVertex, TextInstance>(vertex_buffer_id);
-    vb_data.per_vertex_data.copy_from_slice(&[
+fn text_vertices() -> [TextVertex; VERTEX_PER_RECT as usize] {
+    [
         TextVertex {
             vert_pos: Vector2::new(0., 1.),
             uv: Vector2::new(0., 0.),
@@ -278,6 +286,5 @@ fn fill_vertex_data(context: &mut Context, vertex_buffer_id: DivisionId) {
             vert_pos: Vector2::new(1., 1.),
             uv: Vector2::new(1., 0.),
         },
-    ]);
-    vb_data.vertex_indices.copy_from_slice(&[0, 1, 2, 2, 3, 0]);
+    ]
 }
@@ -8,6 +8,7 @@ use super::ffi::{
     context::{division_engine_context_initialize, DivisionContext},
     settings::DivisionSettings, render_pass_instance::DivisionColor,
 };
+use super::{ShaderCompileError, ShaderValidationError};
 
 pub type Context = DivisionContext;
 
@@ -15,6 +16,22 @@ pub type Context = DivisionContext;
 pub enum Error {
     Core(String),
     CInterface { error_code: i32, message: String },
+    ShaderValidation(ShaderValidationError),
+    ShaderCompilation(ShaderCompileError),
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::Core(message) => write!(f, "{message}"),
+            Error::CInterface {
+                error_code,
+                message,
+            } => write!(f, "{message} (error code {error_code})"),
+            Error::ShaderValidation(e) => write!(f, "{e}"),
+            Error::ShaderCompilation(e) => write!(f, "{e}"),
+        }
+    }
 }
 
 impl Context {
@@ -1,11 +1,17 @@
 use std::{
+    collections::HashMap,
     ffi::{c_char, c_void, CStr, CString},
+    io,
     mem::ManuallyDrop,
+    path::{Path, PathBuf},
     ptr::null_mut,
+    time::Instant,
 };
 
+use division_math::Vector2;
+
 use super::{
-    context::Context,
+    context::{Context, DivisionId},
     context::Error,
     ffi::{
         context::{
@@ -13,24 +19,69 @@ use super::{
             DivisionContext,
         },
         lifecycle::DivisionLifecycle,
-        renderer::division_engine_renderer_run_loop,
+        renderer::{
+            division_engine_renderer_run_loop, division_engine_renderer_stop_loop,
+        },
+        input::DivisionInput,
         settings::DivisionSettings,
+        window::DivisionWindowEvent,
     },
-    LifecycleManager, LifecycleManagerBuilder,
+    frame_time::FrameTimer,
+    input::{empty_input, InputState},
+    input_recording::{InputRecording, InputRecordingWriter},
+    texture::TextureLayout,
+    FixedTimestep, LifecycleManager, LifecycleManagerBuilder, ShaderReflection,
+    WindowEvent,
 };
 
 pub struct CoreRunner {
     title: CString,
     settings: DivisionSettings,
+    fixed_timestep: Option<FixedTimestep>,
+    input_source: InputSource,
+    input_recording_path: Option<PathBuf>,
+}
+
+pub(crate) struct RunnerState {
+    pub exit_code: Option<i32>,
+    pub input_state: InputState,
+    pub framebuffer_size: Option<Vector2>,
+    pub window_size: Option<Vector2>,
+    /// Reflection of the shader of each render pass descriptor,
+    /// that render pass instances are validated against
+    pub render_pass_reflections: HashMap<DivisionId, ShaderReflection>,
+    pub texture_layouts: HashMap<DivisionId, TextureLayout>,
+    is_initialized: bool,
+    input_source: InputSource,
+}
+
+enum InputSource {
+    Live,
+    Record {
+        writer: InputRecordingWriter,
+        error: Option<io::Error>,
+    },
+    Replay {
+        recording: InputRecording,
+        next_frame: usize,
+    },
 }
 
+// Both bridge data types start with the runner state pointer,
+// so it can be reached from the context without knowing the generic type
+#[repr(C)]
 struct ContextPreInitBridgeData<T: LifecycleManagerBuilder> {
+    pub runner_state: *mut RunnerState,
     pub lifecycle_manager_builder: T,
+    pub fixed_timestep: Option<FixedTimestep>,
 }
 
+#[repr(C)]
 struct ContextPostInitBridgeData<T: LifecycleManager> {
+    pub runner_state: *mut RunnerState,
     pub lifecycle_manager: T,
     pub context: Box<Context>,
+    pub frame_timer: FrameTimer,
 }
 
 impl CoreRunner {
@@ -42,6 +93,9 @@ impl CoreRunner {
                 window_height: 512,
                 window_title: null_mut(),
             },
+            fixed_timestep: None,
+            input_source: InputSource::Live,
+            input_recording_path: None,
         };
         builder
     }
@@ -58,24 +112,121 @@ impl CoreRunner {
         self
     }
 
+    /// Enables `LifecycleManager::fixed_update` calls with the given timestep
+    pub fn fixed_timestep(mut self, fixed_timestep: FixedTimestep) -> Self {
+        self.fixed_timestep = Some(fixed_timestep);
+        self
+    }
+
+    /// Records the input and the delta of every frame to `path`. Frames are written
+    /// as they happen, so a crash keeps the frames before it. Window events
+    /// aren't recorded
+    pub fn record_input(mut self, path: &Path) -> Self {
+        self.input_source = InputSource::Live;
+        self.input_recording_path = Some(path.to_path_buf());
+        self
+    }
+
+    /// Feeds the recorded input and frame deltas instead of the live ones,
+    /// then stops the loop after the last recorded frame
+    pub fn replay_input(mut self, recording: InputRecording) -> Self {
+        self.input_source = InputSource::Replay {
+            recording,
+            next_frame: 0,
+        };
+        self.input_recording_path = None;
+        self
+    }
+
+    /// Runs the loop until the window is closed or `Context::request_exit` is called.
+    /// Returns the requested exit code, or zero if the window was closed
     pub fn run<TManager: LifecycleManagerBuilder>(
         self,
         lifecycle_manager_builder: TManager,
-    ) -> Result<(), Error> {
+    ) -> Result<i32, Error> {
+        let input_source = match &self.input_recording_path {
+            Some(path) => InputSource::Record {
+                writer: InputRecordingWriter::create(path)?,
+                error: None,
+            },
+            None => self.input_source,
+        };
+
         let context = Context::new(self.title, self.settings)?;
-        run(context, lifecycle_manager_builder);
+        let runner_state = run(
+            context,
+            lifecycle_manager_builder,
+            self.fixed_timestep,
+            input_source,
+        );
 
-        Ok(())
+        if let InputSource::Record { error: Some(e), .. } = &runner_state.input_source {
+            return Err(Error::Core(format!("Failed to write an input recording: {e}")));
+        }
+
+        Ok(runner_state.exit_code.unwrap_or(0))
+    }
+}
+
+impl Context {
+    /// Finishes the current frame, then calls `LifecycleManager::cleanup`,
+    /// finalizes the context and makes `CoreRunner::run` return `exit_code`
+    pub fn request_exit(&mut self, exit_code: i32) {
+        self.runner_state_mut().exit_code = Some(exit_code);
+        unsafe {
+            division_engine_renderer_stop_loop(self);
+        }
+    }
+
+    pub(crate) fn runner_state(&self) -> &RunnerState {
+        unsafe { &**(self.user_data as *const *mut RunnerState) }
+    }
+
+    pub(crate) fn runner_state_mut(&mut self) -> &mut RunnerState {
+        unsafe { &mut **(self.user_data as *const *mut RunnerState) }
+    }
+}
+
+impl RunnerState {
+    /// Input of the frame being replayed, or `None` if the input is live
+    pub(crate) fn replayed_input(&self) -> Option<DivisionInput> {
+        match &self.input_source {
+            InputSource::Replay {
+                recording,
+                next_frame,
+            } => Some(
+                recording
+                    .frames()
+                    .get(next_frame.saturating_sub(1))
+                    .map_or_else(|| empty_input(0, 0), |f| f.to_input()),
+            ),
+            _ => None,
+        }
     }
 }
 
 fn run<T: LifecycleManagerBuilder>(
     context_ptr: *mut Context,
     lifecycle_manager_builder: T,
-) {
+    fixed_timestep: Option<FixedTimestep>,
+    input_source: InputSource,
+) -> RunnerState {
+    let mut runner_state = RunnerState {
+        exit_code: None,
+        input_state: InputState::new(),
+        framebuffer_size: None,
+        window_size: None,
+        render_pass_reflections: HashMap::new(),
+        texture_layouts: HashMap::new(),
+        is_initialized: false,
+        input_source,
+    };
+
     unsafe {
         let preinit_data = ManuallyDrop::new(Box::new(ContextPreInitBridgeData {
+            runner_state: &mut runner_state,
             lifecycle_manager_builder,
+            fixed_timestep,
         }));
 
         (*context_ptr).user_data =
@@ -88,11 +239,14 @@ fn run<T: LifecycleManagerBuilder>(
                 ready_to_draw_callback: update_callback::<T::LifecycleManager>,
                 free_callback: free_callback::<T::LifecycleManager>,
                 error_callback: error_callback::<T::LifecycleManager>,
+                window_event_callback: window_event_callback::<T::LifecycleManager>,
             },
         );
 
         division_engine_renderer_run_loop(context_ptr);
     }
+
+    runner_state
 }
 
 unsafe extern "C" fn init_callback<T: LifecycleManagerBuilder>(
@@ -104,18 +258,75 @@ unsafe extern "C" fn init_callback<T: LifecycleManagerBuilder>(
     let lifecycle_manager = pre_init.lifecycle_manager_builder.build(&mut ctx);
 
     let mut post_init_data_ptr = ManuallyDrop::new(Box::new(ContextPostInitBridgeData {
+        runner_state: pre_init.runner_state,
         context: ManuallyDrop::into_inner(ctx),
-        lifecycle_manager
+        lifecycle_manager,
+        frame_timer: FrameTimer::new(pre_init.fixed_timestep),
     }));
 
     post_init_data_ptr.context.user_data = post_init_data_ptr.as_ref()
         as *const ContextPostInitBridgeData<T::LifecycleManager>
         as *const c_void;
+    (*post_init_data_ptr.runner_state).is_initialized = true;
 }
 
 unsafe extern "C" fn update_callback<T: LifecycleManager>(ctx: *mut DivisionContext) {
     let owner = get_delegate_mut::<ContextPostInitBridgeData<T>>(&mut *ctx);
-    owner.lifecycle_manager.draw(&mut owner.context);
+    let runner_state = &mut *owner.runner_state;
+
+    let input = match next_frame_input(runner_state, owner) {
+        Some(input) => input,
+        None => {
+            runner_state.exit_code.get_or_insert(0);
+            division_engine_renderer_stop_loop(ctx);
+            return;
+        }
+    };
+    let frame_start_time = owner.frame_timer.frame_time().total;
+    runner_state.input_state.update(&input, frame_start_time);
+
+    while let Some(fixed_time) = owner.frame_timer.next_fixed_step() {
+        owner
+            .lifecycle_manager
+            .fixed_update(&mut owner.context, &fixed_time);
+    }
+
+    let frame_time = owner.frame_timer.frame_time();
+    owner.lifecycle_manager.draw(&mut owner.context, &frame_time);
+}
+
+/// Begins the frame and returns its input, or `None` when the replay is finished.
+/// The replayed input borrows the text from the recording
+fn next_frame_input<T: LifecycleManager>(
+    runner_state: &mut RunnerState,
+    owner: &mut ContextPostInitBridgeData<T>,
+) -> Option<DivisionInput> {
+    match &mut runner_state.input_source {
+        InputSource::Live => {
+            owner.frame_timer.begin_frame(Instant::now());
+            Some(owner.context.get_input())
+        }
+        InputSource::Record { writer, error } => {
+            owner.frame_timer.begin_frame(Instant::now());
+            let input = owner.context.get_input();
+
+            // Keeps the first error and stops recording
+            if error.is_none() {
+                let delta = owner.frame_timer.frame_time().delta;
+                *error = writer.write_frame(delta, &input).err();
+            }
+            Some(input)
+        }
+        InputSource::Replay {
+            recording,
+            next_frame,
+        } => {
+            let frame = recording.frames().get(*next_frame)?;
+            *next_frame += 1;
+            owner.frame_timer.begin_frame_with_delta(frame.delta);
+            Some(frame.to_input())
+        }
+    }
 }
 
 unsafe extern "C" fn free_callback<T: LifecycleManager>(ctx: *mut DivisionContext) {
@@ -141,6 +352,42 @@ unsafe extern "C" fn error_callback<T: LifecycleManager>(
     );
 }
 
+unsafe extern "C" fn window_event_callback<T: LifecycleManager>(
+    ctx: *mut DivisionContext,
+    event: *const DivisionWindowEvent,
+) -> bool {
+    // Unknown event types are skipped
+    let Some(event) = WindowEvent::from_division_event(&*event) else {
+        return true;
+    };
+
+    // Both bridge data types start with the runner state pointer
+    let runner_state = &mut **((*ctx).user_data as *const *mut RunnerState);
+    if let WindowEvent::Resized {
+        frame_buffer_size,
+        window_size,
+    } = event
+    {
+        runner_state.framebuffer_size = Some(frame_buffer_size);
+        runner_state.window_size = Some(window_size);
+    }
+
+    // The lifecycle manager doesn't exist yet, e.g. on a resize
+    // while the window is created
+    if !runner_state.is_initialized {
+        return true;
+    }
+
+    let owner = get_delegate_mut::<ContextPostInitBridgeData<T>>(&mut *ctx);
+    owner.lifecycle_manager.window_event(&mut owner.context, &event);
+    match event {
+        WindowEvent::CloseRequested => {
+            owner.lifecycle_manager.close_requested(&mut owner.context)
+        }
+        _ => true,
+    }
+}
+
 #[inline(always)]
 fn get_delegate_mut<'a, 'b, T>(ctx: &'a mut DivisionContext) -> &'b mut T {
     unsafe { &mut *(ctx.user_data as *mut T) }
@@ -0,0 +1,173 @@
+/// Rectangle of an atlas in pixels, with the origin in the first pixel of the atlas
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct AtlasRect {
+    pub x: usize,
+    pub y: usize,
+    pub width: usize,
+    pub height: usize,
+}
+
+/// Packs rectangles of any size into a fixed size atlas with the skyline
+/// bottom-left algorithm.
+///
+/// The skyline is the top edge of the packed rectangles, stored as horizontal
+/// segments. A new rectangle is placed on the segment where its top is the lowest,
+/// so rows of different heights don't waste the space above the lower ones.
+/// Rectangles can't be removed one by one, the packer can only be cleared
+pub struct AtlasPacker {
+    width: usize,
+    height: usize,
+    padding: usize,
+    skyline: Vec<SkylineSegment>,
+    used_area: usize,
+}
+
+#[derive(Clone, Copy, Debug)]
+struct SkylineSegment {
+    x: usize,
+    y: usize,
+    width: usize,
+}
+
+impl AtlasPacker {
+    pub fn new(width: usize, height: usize) -> AtlasPacker {
+        AtlasPacker {
+            width,
+            height,
+            padding: 0,
+            skyline: vec![SkylineSegment { x: 0, y: 0, width }],
+            used_area: 0,
+        }
+    }
+
+    /// Empty pixels to the right and above every rectangle,
+    /// so the samples of the neighbours don't bleed into each other.
+    /// Nothing bleeds past the atlas edges, so the padding may be cut by them
+    pub fn with_padding(mut self, padding: usize) -> Self {
+        self.padding = padding;
+        self
+    }
+
+    #[inline]
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    #[inline]
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Area of the packed rectangles, including the padding
+    #[inline]
+    pub fn used_area(&self) -> usize {
+        self.used_area
+    }
+
+    /// Finds a place for the rectangle, or returns `None` if there is no space left
+    pub fn pack(&mut self, width: usize, height: usize) -> Option<AtlasRect> {
+        let mut best: Option<(usize, usize)> = None;
+        let mut best_top = usize::MAX;
+        let mut best_width = usize::MAX;
+
+        for i in 0..self.skyline.len() {
+            let Some(y) = self.fit(i, width, height) else {
+                continue;
+            };
+
+            let top = y + height + self.padding;
+            let segment_width = self.skyline[i].width;
+            if top < best_top || (top == best_top && segment_width < best_width) {
+                best = Some((i, y));
+                best_top = top;
+                best_width = segment_width;
+            }
+        }
+
+        let (index, y) = best?;
+        let x = self.skyline[index].x;
+        let padded_width = (width + self.padding).min(self.width - x);
+        let padded_top = (y + height + self.padding).min(self.height);
+        self.add_segment(
+            index,
+            SkylineSegment {
+                x,
+                y: padded_top,
+                width: padded_width,
+            },
+        );
+        self.used_area += padded_width * (padded_top - y);
+
+        Some(AtlasRect {
+            x,
+            y,
+            width,
+            height,
+        })
+    }
+
+    /// Forgets all packed rectangles
+    pub fn clear(&mut self) {
+        self.skyline.clear();
+        self.skyline.push(SkylineSegment {
+            x: 0,
+            y: 0,
+            width: self.width,
+        });
+        self.used_area = 0;
+    }
+
+    /// Returns the bottom of a rectangle placed at the start of the segment,
+    /// which is the top of the highest segment under it and its right padding
+    fn fit(&self, index: usize, width: usize, height: usize) -> Option<usize> {
+        let x = self.skyline[index].x;
+        if x + width > self.width {
+            return None;
+        }
+
+        let mut y = 0;
+        let mut width_left = (width + self.padding) as isize;
+        for segment in &self.skyline[index..] {
+            if width_left <= 0 {
+                break;
+            }
+
+            y = y.max(segment.y);
+            if y + height > self.height {
+                return None;
+            }
+            width_left -= segment.width as isize;
+        }
+
+        Some(y)
+    }
+
+    fn add_segment(&mut self, index: usize, segment: SkylineSegment) {
+        self.skyline.insert(index, segment);
+
+        // Cuts the segments covered by the new one
+        let right = segment.x + segment.width;
+        let next = index + 1;
+        while next < self.skyline.len() && self.skyline[next].x < right {
+            let covered = &mut self.skyline[next];
+            let covered_right = covered.x + covered.width;
+
+            if covered_right <= right {
+                self.skyline.remove(next);
+            } else {
+                covered.width = covered_right - right;
+                covered.x = right;
+                break;
+            }
+        }
+
+        self.skyline.dedup_by(|next, prev| {
+            if prev.y == next.y {
+                prev.width += next.width;
+                true
+            } else {
+                false
+            }
+        });
+    }
+}
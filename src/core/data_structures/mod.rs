@@ -1,6 +1,8 @@
 #[cfg(test)]
 mod tests;
 
+pub mod atlas_packer;
 pub mod sparse_set;
 
+pub use atlas_packer::*;
 pub use sparse_set::*;
\ No newline at end of file
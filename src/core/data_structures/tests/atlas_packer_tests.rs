@@ -0,0 +1,143 @@
+use crate::core::{AtlasPacker, AtlasRect};
+
+fn overlaps(a: &AtlasRect, b: &AtlasRect) -> bool {
+    a.x < b.x + b.width
+        && b.x < a.x + a.width
+        && a.y < b.y + b.height
+        && b.y < a.y + a.height
+}
+
+#[test]
+fn pack_places_first_rect_at_origin() {
+    let mut packer = AtlasPacker::new(64, 64);
+
+    assert_eq!(
+        packer.pack(10, 20),
+        Some(AtlasRect {
+            x: 0,
+            y: 0,
+            width: 10,
+            height: 20
+        })
+    );
+    assert_eq!(packer.used_area(), 200);
+}
+
+#[test]
+fn pack_fills_row_before_going_up() {
+    let mut packer = AtlasPacker::new(30, 30);
+
+    let a = packer.pack(10, 10).unwrap();
+    let b = packer.pack(10, 10).unwrap();
+    let c = packer.pack(10, 10).unwrap();
+    let d = packer.pack(10, 10).unwrap();
+
+    assert_eq!((a.x, a.y), (0, 0));
+    assert_eq!((b.x, b.y), (10, 0));
+    assert_eq!((c.x, c.y), (20, 0));
+    assert_eq!((d.x, d.y), (0, 10));
+}
+
+#[test]
+fn pack_uses_space_above_lower_rects() {
+    let mut packer = AtlasPacker::new(20, 30);
+
+    packer.pack(10, 30).unwrap();
+    let low = packer.pack(10, 10).unwrap();
+    let above_low = packer.pack(10, 20).unwrap();
+
+    assert_eq!((low.x, low.y), (10, 0));
+    assert_eq!((above_low.x, above_low.y), (10, 10));
+}
+
+#[test]
+fn pack_spans_multiple_skyline_segments() {
+    let mut packer = AtlasPacker::new(30, 30);
+
+    packer.pack(10, 5).unwrap();
+    packer.pack(10, 10).unwrap();
+    packer.pack(10, 5).unwrap();
+    let wide = packer.pack(30, 10).unwrap();
+
+    assert_eq!((wide.x, wide.y), (0, 10));
+}
+
+#[test]
+fn pack_returns_none_when_full() {
+    let mut packer = AtlasPacker::new(16, 16);
+
+    assert!(packer.pack(17, 1).is_none());
+    assert!(packer.pack(16, 16).is_some());
+    assert!(packer.pack(1, 1).is_none());
+}
+
+#[test]
+fn pack_adds_padding() {
+    let mut packer = AtlasPacker::new(32, 32).with_padding(2);
+
+    let a = packer.pack(10, 10).unwrap();
+    let b = packer.pack(10, 10).unwrap();
+
+    assert_eq!((a.width, a.height), (10, 10));
+    assert_eq!((b.x, b.y), (12, 0));
+    assert_eq!(packer.used_area(), 2 * 12 * 12);
+}
+
+#[test]
+fn padding_may_go_past_atlas_edges() {
+    let mut packer = AtlasPacker::new(22, 10).with_padding(2);
+
+    let a = packer.pack(10, 10).unwrap();
+    let b = packer.pack(10, 10).unwrap();
+
+    assert_eq!((a.x, a.y), (0, 0));
+    assert_eq!((b.x, b.y), (12, 0));
+    assert_eq!(packer.used_area(), 12 * 10 + 10 * 10);
+    assert!(packer.pack(1, 1).is_none());
+}
+
+#[test]
+fn padding_still_separates_from_higher_neighbours() {
+    let mut packer = AtlasPacker::new(30, 30).with_padding(2);
+
+    packer.pack(10, 5).unwrap();
+    let high = packer.pack(10, 20).unwrap();
+    // Fits above the low rect, but its padding would touch the high one
+    let c = packer.pack(12, 3).unwrap();
+
+    assert_eq!((high.x, high.y), (12, 0));
+    assert_eq!((c.x, c.y), (0, 22));
+}
+
+#[test]
+fn packed_rects_do_not_overlap() {
+    let mut packer = AtlasPacker::new(128, 128);
+    let mut rects = Vec::new();
+
+    for i in 0..64 {
+        let width = 3 + (i * 7) % 13;
+        let height = 2 + (i * 5) % 17;
+        if let Some(rect) = packer.pack(width, height) {
+            assert!(rect.x + rect.width <= 128 && rect.y + rect.height <= 128);
+            rects.push(rect);
+        }
+    }
+
+    assert!(rects.len() > 32);
+    for (i, a) in rects.iter().enumerate() {
+        for b in &rects[i + 1..] {
+            assert!(!overlaps(a, b), "{a:?} overlaps {b:?}");
+        }
+    }
+}
+
+#[test]
+fn clear_frees_the_atlas() {
+    let mut packer = AtlasPacker::new(16, 16);
+    packer.pack(16, 16).unwrap();
+
+    packer.clear();
+
+    assert_eq!(packer.used_area(), 0);
+    assert_eq!(packer.pack(16, 16).map(|r| (r.x, r.y)), Some((0, 0)));
+}
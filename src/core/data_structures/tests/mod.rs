@@ -1 +1,2 @@
+mod atlas_packer_tests;
 mod sparse_set_tests;
\ No newline at end of file
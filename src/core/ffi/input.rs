@@ -1,7 +1,10 @@
+use std::{fmt::Display, str::FromStr};
+
 use super::context::DivisionContext;
-use super::keycode::Keycode;
+use super::keycode::{Keycode, ParseInputNameError};
 
 #[repr(u32)]
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
 pub enum MouseButton {
     Left = 0,
     Right = 1,
@@ -9,26 +12,52 @@ pub enum MouseButton {
     Other(u32),
 }
 
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum DivisionScrollDeltaUnit {
+    Line = 0,
+    Pixel = 1,
+}
+
 #[repr(C)]
+#[derive(Clone, Copy)]
 pub struct DivisionMouseInput
 {
     pub pos_x: i32,
     pub pos_y: i32,
 
-    pub mouse_button_state_mask: u32
+    pub mouse_button_state_mask: u32,
+
+    pub scroll_delta_x: f32,
+    pub scroll_delta_y: f32,
+    /// One of `DivisionScrollDeltaUnit`, use `scroll_unit` to read it
+    pub scroll_delta_unit: u32,
 }
 
 #[repr(C)]
+#[derive(Clone, Copy)]
 pub struct DivisionKeyboardInput
 {
-    key_state_mask: [u32; 4]
+    pub(crate) key_state_mask: [u32; 4],
+    pub(crate) key_repeat_mask: [u32; 4],
 }
 
+/// Unicode characters entered since the previous frame, already translated by the
+/// keyboard layout and modifiers. The pointer is valid until the next input poll
 #[repr(C)]
+#[derive(Clone, Copy)]
+pub struct DivisionTextInput
+{
+    pub characters: *const u32,
+    pub character_count: u32,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
 pub struct DivisionInput
 {
     pub mouse: DivisionMouseInput,
     pub keyboard: DivisionKeyboardInput,
+    pub text: DivisionTextInput,
 }
 
 extern "C" {
@@ -39,26 +68,117 @@ extern "C" {
 
 impl DivisionKeyboardInput {
     pub fn is_key_pressed(&self, keycode: Keycode) -> bool {
-        let keycode = keycode as u32;
-        let mask_index = keycode / 32;
-        let mask_offset = keycode % 32;
+        is_key_in_mask(&self.key_state_mask, keycode)
+    }
 
-        (self.key_state_mask[mask_index as usize] & (1 << mask_offset)) != 0
+    /// Returns `true` if the OS sent a key repeat for the held key this frame
+    pub fn is_key_repeated(&self, keycode: Keycode) -> bool {
+        is_key_in_mask(&self.key_repeat_mask, keycode)
+    }
+}
+
+impl DivisionTextInput {
+    pub fn characters(&self) -> impl Iterator<Item = char> + '_ {
+        let codepoints = match self.characters.is_null() {
+            true => &[],
+            false => unsafe {
+                std::slice::from_raw_parts(self.characters, self.character_count as usize)
+            },
+        };
+
+        codepoints.iter().filter_map(|c| char::from_u32(*c))
     }
 }
 
 impl DivisionMouseInput {
+    /// Returns `None` if the core sent a unit the bindings don't know
+    pub fn scroll_unit(&self) -> Option<DivisionScrollDeltaUnit> {
+        DivisionScrollDeltaUnit::try_from(self.scroll_delta_unit).ok()
+    }
+
+    /// Buttons that don't fit in the state mask are never pressed
     pub fn is_button_pressed(&self, mouse_button: MouseButton) -> bool {
         let mouse_button: u32 = mouse_button.into();
-        (self.mouse_button_state_mask & (1 << mouse_button)) != 0
+        mouse_button < u32::BITS
+            && (self.mouse_button_state_mask & (1 << mouse_button)) != 0
+    }
+}
+
+impl TryFrom<u32> for DivisionScrollDeltaUnit {
+    type Error = u32;
+
+    fn try_from(value: u32) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(DivisionScrollDeltaUnit::Line),
+            1 => Ok(DivisionScrollDeltaUnit::Pixel),
+            _ => Err(value),
+        }
+    }
+}
+
+impl From<DivisionScrollDeltaUnit> for u32 {
+    fn from(value: DivisionScrollDeltaUnit) -> Self {
+        match value {
+            DivisionScrollDeltaUnit::Line => 0,
+            DivisionScrollDeltaUnit::Pixel => 1,
+        }
     }
 }
 
 impl From<MouseButton> for u32 {
     fn from(value: MouseButton) -> Self {
-        let ptr = &value as *const MouseButton as *const u32;
-        unsafe {
-            *ptr
+        match value {
+            MouseButton::Left => 0,
+            MouseButton::Right => 1,
+            MouseButton::Middle => 2,
+            MouseButton::Other(button) => button,
+        }
+    }
+}
+
+impl From<u32> for MouseButton {
+    fn from(value: u32) -> Self {
+        match value {
+            0 => MouseButton::Left,
+            1 => MouseButton::Right,
+            2 => MouseButton::Middle,
+            button => MouseButton::Other(button),
+        }
+    }
+}
+
+impl Display for MouseButton {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MouseButton::Left => f.write_str("Left"),
+            MouseButton::Right => f.write_str("Right"),
+            MouseButton::Middle => f.write_str("Middle"),
+            MouseButton::Other(button) => write!(f, "{button}"),
+        }
+    }
+}
+
+impl FromStr for MouseButton {
+    type Err = ParseInputNameError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "Left" => Ok(MouseButton::Left),
+            "Right" => Ok(MouseButton::Right),
+            "Middle" => Ok(MouseButton::Middle),
+            _ => s.parse::<u32>().map(MouseButton::from).map_err(|_| {
+                ParseInputNameError {
+                    name: s.to_string(),
+                }
+            }),
         }
     }
-}
\ No newline at end of file
+}
+
+fn is_key_in_mask(mask: &[u32; 4], keycode: Keycode) -> bool {
+    let keycode = keycode as u32;
+    let mask_index = keycode / 32;
+    let mask_offset = keycode % 32;
+
+    (mask[mask_index as usize] & (1 << mask_offset)) != 0
+}
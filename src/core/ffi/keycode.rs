@@ -1,4 +1,7 @@
+use std::{fmt::Display, str::FromStr};
+
 #[repr(C)]
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
 pub enum Keycode {
     // LATIN CHARACTERS
     Q = 1,
@@ -129,3 +132,170 @@ pub enum Keycode {
     
     Eject = 113,
 }
+
+impl Keycode {
+    pub const FIRST: Keycode = Keycode::Q;
+    pub const LAST: Keycode = Keycode::Eject;
+
+    pub fn from_u32(value: u32) -> Option<Keycode> {
+        if value < Self::FIRST as u32 || value > Self::LAST as u32 {
+            return None;
+        }
+
+        // Keycodes are contiguous from the first to the last one
+        Some(unsafe { std::mem::transmute(value) })
+    }
+
+    pub fn iter() -> impl Iterator<Item = Keycode> {
+        (Self::FIRST as u32..=Self::LAST as u32).filter_map(Keycode::from_u32)
+    }
+
+    /// Stable name of the key, e.g. for config files.
+    /// It never changes between versions, unlike the `Debug` output
+    pub fn name(self) -> &'static str {
+        match self {
+            Keycode::Q => "Q",
+            Keycode::W => "W",
+            Keycode::E => "E",
+            Keycode::R => "R",
+            Keycode::T => "T",
+            Keycode::Y => "Y",
+            Keycode::U => "U",
+            Keycode::I => "I",
+            Keycode::O => "O",
+            Keycode::P => "P",
+            Keycode::A => "A",
+            Keycode::S => "S",
+            Keycode::D => "D",
+            Keycode::F => "F",
+            Keycode::G => "G",
+            Keycode::H => "H",
+            Keycode::J => "J",
+            Keycode::K => "K",
+            Keycode::L => "L",
+            Keycode::Z => "Z",
+            Keycode::X => "X",
+            Keycode::C => "C",
+            Keycode::V => "V",
+            Keycode::B => "B",
+            Keycode::N => "N",
+            Keycode::M => "M",
+            Keycode::Num0 => "Num0",
+            Keycode::Num1 => "Num1",
+            Keycode::Num2 => "Num2",
+            Keycode::Num3 => "Num3",
+            Keycode::Num4 => "Num4",
+            Keycode::Num5 => "Num5",
+            Keycode::Num6 => "Num6",
+            Keycode::Num7 => "Num7",
+            Keycode::Num8 => "Num8",
+            Keycode::Num9 => "Num9",
+            Keycode::Minus => "Minus",
+            Keycode::Equal => "Equal",
+            Keycode::LsquareBracket => "LsquareBracket",
+            Keycode::RsquareBracket => "RsquareBracket",
+            Keycode::Semicolon => "Semicolon",
+            Keycode::Quote => "Quote",
+            Keycode::Backslash => "Backslash",
+            Keycode::Comma => "Comma",
+            Keycode::Dot => "Dot",
+            Keycode::Slash => "Slash",
+            Keycode::Tilde => "Tilde",
+            Keycode::Paragraph => "Paragraph",
+            Keycode::Numpad0 => "Numpad0",
+            Keycode::Numpad1 => "Numpad1",
+            Keycode::Numpad2 => "Numpad2",
+            Keycode::Numpad3 => "Numpad3",
+            Keycode::Numpad4 => "Numpad4",
+            Keycode::Numpad5 => "Numpad5",
+            Keycode::Numpad6 => "Numpad6",
+            Keycode::Numpad7 => "Numpad7",
+            Keycode::Numpad8 => "Numpad8",
+            Keycode::Numpad9 => "Numpad9",
+            Keycode::NumpadNumLockClear => "NumpadNumLockClear",
+            Keycode::NumpadDiv => "NumpadDiv",
+            Keycode::NumpadMul => "NumpadMul",
+            Keycode::NumpadSub => "NumpadSub",
+            Keycode::NumpadAdd => "NumpadAdd",
+            Keycode::NumpadEnter => "NumpadEnter",
+            Keycode::NumpadDot => "NumpadDot",
+            Keycode::NumpadEqual => "NumpadEqual",
+            Keycode::F1 => "F1",
+            Keycode::F2 => "F2",
+            Keycode::F3 => "F3",
+            Keycode::F4 => "F4",
+            Keycode::F5 => "F5",
+            Keycode::F6 => "F6",
+            Keycode::F7 => "F7",
+            Keycode::F8 => "F8",
+            Keycode::F9 => "F9",
+            Keycode::F10 => "F10",
+            Keycode::F11 => "F11",
+            Keycode::F12 => "F12",
+            Keycode::F13 => "F13",
+            Keycode::F14 => "F14",
+            Keycode::F15 => "F15",
+            Keycode::F16 => "F16",
+            Keycode::F17 => "F17",
+            Keycode::F18 => "F18",
+            Keycode::F19 => "F19",
+            Keycode::Esc => "Esc",
+            Keycode::Enter => "Enter",
+            Keycode::Space => "Space",
+            Keycode::LShift => "LShift",
+            Keycode::RShift => "RShift",
+            Keycode::LOptionLAlt => "LOptionLAlt",
+            Keycode::ROptionRAlt => "ROptionRAlt",
+            Keycode::LCmdLCtrl => "LCmdLCtrl",
+            Keycode::RCmdRCtrl => "RCmdRCtrl",
+            Keycode::LControlLWin => "LControlLWin",
+            Keycode::RControlRWin => "RControlRWin",
+            Keycode::CapsLock => "CapsLock",
+            Keycode::Tab => "Tab",
+            Keycode::LeftArrow => "LeftArrow",
+            Keycode::RightArrow => "RightArrow",
+            Keycode::UpArrow => "UpArrow",
+            Keycode::DownArrow => "DownArrow",
+            Keycode::Backspace => "Backspace",
+            Keycode::Delete => "Delete",
+            Keycode::Insert => "Insert",
+            Keycode::Home => "Home",
+            Keycode::End => "End",
+            Keycode::PageUp => "PageUp",
+            Keycode::PageDown => "PageDown",
+            Keycode::Print => "Print",
+            Keycode::Scroll => "Scroll",
+            Keycode::Pause => "Pause",
+            Keycode::Eject => "Eject",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseInputNameError {
+    pub name: String,
+}
+
+impl Display for Keycode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for Keycode {
+    type Err = ParseInputNameError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Keycode::iter()
+            .find(|k| k.name() == s)
+            .ok_or_else(|| ParseInputNameError {
+                name: s.to_string(),
+            })
+    }
+}
+
+impl Display for ParseInputNameError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Unknown input name: `{}`", self.name)
+    }
+}
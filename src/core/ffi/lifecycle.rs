@@ -1,9 +1,12 @@
 use std::ffi::c_char;
 
-use super::context::DivisionContext;
+use super::{context::DivisionContext, window::DivisionWindowEvent};
 
 pub type DivisionErrorFunc = unsafe extern "C" fn(*mut DivisionContext, i32, *const c_char);
 pub type DivisionLifecycleFunc = unsafe extern "C" fn(*mut DivisionContext);
+/// Returns `false` to veto the default handling of the event (e.g. closing the window)
+pub type DivisionWindowEventFunc =
+    unsafe extern "C" fn(*mut DivisionContext, *const DivisionWindowEvent) -> bool;
 
 #[repr(C)]
 pub struct DivisionLifecycle {
@@ -11,4 +14,5 @@ pub struct DivisionLifecycle {
     pub ready_to_draw_callback: DivisionLifecycleFunc,
     pub free_callback: DivisionLifecycleFunc,
     pub error_callback: DivisionErrorFunc,
+    pub window_event_callback: DivisionWindowEventFunc,
 }
\ No newline at end of file
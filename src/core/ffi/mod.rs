@@ -5,6 +5,7 @@ pub mod keycode;
 pub mod lifecycle;
 pub mod render_pass_descriptor;
 pub mod render_pass_instance;
+pub mod render_target;
 pub mod renderer;
 pub mod settings;
 pub mod shader;
@@ -13,3 +14,4 @@ pub mod stb;
 pub mod texture;
 pub mod uniform_buffer;
 pub mod vertex_buffer;
+pub mod window;
@@ -22,6 +22,8 @@ bitflags! {
     pub struct DivisionRenderPassDescriptorCapabilityMask: i32 {
         const None = 0;
         const AlphaBlend = 1 << 0;
+        const DepthTest = 1 << 1;
+        const StencilTest = 1 << 2;
     }
 }
 
@@ -55,6 +57,32 @@ pub enum DivisionAlphaBlendOperation {
     Max = 5,
 }
 
+#[repr(i32)]
+#[derive(PartialEq, Clone, Copy)]
+pub enum DivisionCompareFunction {
+    Never = 1,
+    Less = 2,
+    Equal = 3,
+    LessEqual = 4,
+    Greater = 5,
+    NotEqual = 6,
+    GreaterEqual = 7,
+    Always = 8,
+}
+
+#[repr(i32)]
+#[derive(PartialEq, Clone, Copy)]
+pub enum DivisionStencilOperation {
+    Keep = 1,
+    Zero = 2,
+    Replace = 3,
+    IncrementClamp = 4,
+    IncrementWrap = 5,
+    DecrementClamp = 6,
+    DecrementWrap = 7,
+    Invert = 8,
+}
+
 #[repr(C)]
 pub struct DivisionAlphaBlendingOptions {
     pub src: DivisionAlphaBlend,
@@ -63,6 +91,25 @@ pub struct DivisionAlphaBlendingOptions {
     pub constant_blend_color: [f32; 4],
 }
 
+#[repr(C)]
+pub struct DivisionDepthOptions {
+    pub compare_function: DivisionCompareFunction,
+    pub clear_value: f32,
+    pub write_enabled: bool,
+}
+
+#[repr(C)]
+pub struct DivisionStencilOptions {
+    pub compare_function: DivisionCompareFunction,
+    pub reference: u32,
+    pub read_mask: u32,
+    pub write_mask: u32,
+    pub clear_value: u32,
+    pub stencil_fail_operation: DivisionStencilOperation,
+    pub depth_fail_operation: DivisionStencilOperation,
+    pub pass_operation: DivisionStencilOperation,
+}
+
 #[repr(C)]
 pub struct DivisionRenderPassDescriptor {
     pub alpha_blending_options: DivisionAlphaBlendingOptions,
@@ -70,6 +117,8 @@ pub struct DivisionRenderPassDescriptor {
     pub vertex_buffer_id: u32,
     pub capabilities_mask: DivisionRenderPassDescriptorCapabilityMask,
     pub color_mask: DivisionColorMask,
+    pub depth_options: DivisionDepthOptions,
+    pub stencil_options: DivisionStencilOptions,
 }
 
 extern "C" {
@@ -10,9 +10,19 @@ bitflags! {
     pub struct DivisionRenderPassInstanceCapabilityMask: i32 {
         const None = 0;
         const InstancedRendering = 1 << 0;
+        const Scissor = 1 << 1;
+        const Viewport = 1 << 2;
     }
 }
 
+#[repr(i32)]
+#[derive(PartialEq, Clone, Copy)]
+pub enum DivisionLoadAction {
+    Clear = 0,
+    Load = 1,
+    DontCare = 2,
+}
+
 #[repr(C)]
 #[derive(Clone)]
 pub struct DivisionIdWithBinding {
@@ -29,6 +39,16 @@ pub struct DivisionColor {
     pub a: c_float
 }
 
+/// Framebuffer region in pixels, `x` and `y` are its bottom left corner
+#[repr(C)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct DivisionPixelRect {
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+}
+
 #[derive(Clone)]
 #[repr(C)]
 pub struct DivisionRenderPassInstance {
@@ -46,6 +66,8 @@ pub struct DivisionRenderPassInstance {
     pub fragment_texture_count: i32,
     pub render_pass_descriptor_id: u32,
     pub capabilities_mask: DivisionRenderPassInstanceCapabilityMask,
+    pub scissor_rect: DivisionPixelRect,
+    pub viewport_rect: DivisionPixelRect,
 }
 
 extern "C" {
@@ -55,4 +77,12 @@ extern "C" {
         render_pass_instances: *const DivisionRenderPassInstance,
         render_pass_instance_count: u32
     );
+
+    pub fn division_engine_render_pass_instance_submit(
+        ctx: *mut DivisionContext,
+        load_action: DivisionLoadAction,
+        clear_color: *const DivisionColor,
+        render_pass_instances: *const DivisionRenderPassInstance,
+        render_pass_instance_count: u32
+    );
 }
\ No newline at end of file
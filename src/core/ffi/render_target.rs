@@ -0,0 +1,57 @@
+use super::{
+    context::DivisionContext,
+    render_pass_instance::{
+        DivisionColor, DivisionLoadAction, DivisionRenderPassInstance,
+    },
+    texture::DivisionTextureFormat,
+};
+
+#[repr(C)]
+pub struct DivisionRenderTargetDescriptor {
+    pub width: u32,
+    pub height: u32,
+    pub color_format: DivisionTextureFormat,
+    pub has_depth_attachment: bool,
+    pub has_stencil_attachment: bool,
+}
+
+extern "C" {
+    pub fn division_engine_render_target_alloc(
+        ctx: *mut DivisionContext,
+        descriptor: *const DivisionRenderTargetDescriptor,
+        out_render_target_id: *mut u32,
+    ) -> bool;
+
+    pub fn division_engine_render_target_free(
+        ctx: *mut DivisionContext,
+        render_target_id: u32,
+    );
+
+    pub fn division_engine_render_target_color_texture(
+        ctx: *mut DivisionContext,
+        render_target_id: u32,
+    ) -> u32;
+
+    pub fn division_engine_render_target_depth_texture(
+        ctx: *mut DivisionContext,
+        render_target_id: u32,
+        out_texture_id: *mut u32,
+    ) -> bool;
+
+    pub fn division_engine_render_pass_instance_draw_to_target(
+        ctx: *mut DivisionContext,
+        render_target_id: u32,
+        clear_color: *const DivisionColor,
+        render_pass_instances: *const DivisionRenderPassInstance,
+        render_pass_instance_count: u32,
+    );
+
+    pub fn division_engine_render_pass_instance_submit_to_target(
+        ctx: *mut DivisionContext,
+        render_target_id: u32,
+        load_action: DivisionLoadAction,
+        clear_color: *const DivisionColor,
+        render_pass_instances: *const DivisionRenderPassInstance,
+        render_pass_instance_count: u32,
+    );
+}
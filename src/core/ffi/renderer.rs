@@ -2,4 +2,19 @@ use super::context::DivisionContext;
 
 extern "C" {
     pub fn division_engine_renderer_run_loop(ctx: *mut DivisionContext);
+
+    pub fn division_engine_renderer_stop_loop(ctx: *mut DivisionContext);
+
+    pub fn division_engine_renderer_begin_frame(ctx: *mut DivisionContext);
+
+    pub fn division_engine_renderer_end_frame(ctx: *mut DivisionContext);
+
+    pub fn division_engine_renderer_read_framebuffer(
+        ctx: *mut DivisionContext,
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32,
+        out_rgba_pixels: *mut u8,
+    ) -> bool;
 }
@@ -2,13 +2,14 @@ use super::context::DivisionContext;
 use std::ffi::c_char;
 
 #[repr(i32)]
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
 pub enum DivisionShaderType {
     Vertex = 1,
     Fragment = 2,
 }
 
 #[repr(i32)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
 pub enum DivisionShaderVariableType {
     Float = 1,
     Double = 2,
@@ -17,6 +18,55 @@ pub enum DivisionShaderVariableType {
     FVec3 = 5,
     FVec4 = 6,
     FMat4x4 = 7,
+    UInteger = 8,
+    IVec2 = 9,
+    IVec3 = 10,
+    IVec4 = 11,
+    UVec2 = 12,
+    UVec3 = 13,
+    UVec4 = 14,
+    UByte4Normalized = 15,
+    UShort2Normalized = 16,
+    UShort4Normalized = 17,
+}
+
+impl TryFrom<i32> for DivisionShaderType {
+    type Error = i32;
+
+    fn try_from(value: i32) -> Result<Self, Self::Error> {
+        Ok(match value {
+            1 => DivisionShaderType::Vertex,
+            2 => DivisionShaderType::Fragment,
+            _ => return Err(value),
+        })
+    }
+}
+
+impl TryFrom<i32> for DivisionShaderVariableType {
+    type Error = i32;
+
+    fn try_from(value: i32) -> Result<Self, Self::Error> {
+        Ok(match value {
+            1 => DivisionShaderVariableType::Float,
+            2 => DivisionShaderVariableType::Double,
+            3 => DivisionShaderVariableType::Integer,
+            4 => DivisionShaderVariableType::FVec2,
+            5 => DivisionShaderVariableType::FVec3,
+            6 => DivisionShaderVariableType::FVec4,
+            7 => DivisionShaderVariableType::FMat4x4,
+            8 => DivisionShaderVariableType::UInteger,
+            9 => DivisionShaderVariableType::IVec2,
+            10 => DivisionShaderVariableType::IVec3,
+            11 => DivisionShaderVariableType::IVec4,
+            12 => DivisionShaderVariableType::UVec2,
+            13 => DivisionShaderVariableType::UVec3,
+            14 => DivisionShaderVariableType::UVec4,
+            15 => DivisionShaderVariableType::UByte4Normalized,
+            16 => DivisionShaderVariableType::UShort2Normalized,
+            17 => DivisionShaderVariableType::UShort4Normalized,
+            _ => return Err(value),
+        })
+    }
 }
 
 #[repr(C)]
@@ -27,6 +77,44 @@ pub struct DivisionShaderSourceDescriptor {
     pub source_size: i32,
 }
 
+/// Enums written by the C side are read as integers,
+/// an unknown value would be undefined behaviour as a Rust enum
+#[repr(C)]
+pub struct DivisionShaderVertexInput {
+    pub name: *const c_char,
+    /// `DivisionShaderVariableType`
+    pub variable_type: i32,
+    pub location: i32,
+}
+
+#[repr(C)]
+pub struct DivisionShaderUniformBlock {
+    pub name: *const c_char,
+    /// `DivisionShaderType`
+    pub shader_type: i32,
+    pub binding: u32,
+    pub size_bytes: u32,
+}
+
+#[repr(C)]
+pub struct DivisionShaderTexture {
+    pub name: *const c_char,
+    /// `DivisionShaderType`
+    pub shader_type: i32,
+    pub binding: u32,
+}
+
+/// Arrays are owned by the shader program and live until it's freed
+#[repr(C)]
+pub struct DivisionShaderReflection {
+    pub vertex_inputs: *const DivisionShaderVertexInput,
+    pub uniform_blocks: *const DivisionShaderUniformBlock,
+    pub textures: *const DivisionShaderTexture,
+    pub vertex_input_count: i32,
+    pub uniform_block_count: i32,
+    pub texture_count: i32,
+}
+
 extern "C" {
     pub fn division_engine_shader_program_alloc(
         ctx: *mut DivisionContext,
@@ -39,4 +127,17 @@ extern "C" {
         ctx: *mut DivisionContext,
         shader_program_id: u32,
     );
+
+    /// Compiler or linker log of the last failed `division_engine_shader_program_alloc`,
+    /// null if there is none
+    pub fn division_engine_shader_program_last_error(
+        ctx: *mut DivisionContext,
+        out_shader_type: *mut i32,
+    ) -> *const c_char;
+
+    pub fn division_engine_shader_program_get_reflection(
+        ctx: *mut DivisionContext,
+        shader_program_id: u32,
+        out_reflection: *mut DivisionShaderReflection,
+    ) -> bool;
 }
@@ -1,4 +1,7 @@
-use std::ffi::{c_char, c_int};
+use std::ffi::{c_char, c_int, c_void};
+
+pub type StbiWriteFunc =
+    unsafe extern "C" fn(context: *mut c_void, data: *mut c_void, size: c_int);
 
 extern "C" {
     pub fn stbi_load(
@@ -60,6 +63,53 @@ extern "C" {
         w: c_int,
         h: c_int,
         comp: c_int,
+        data: *const f32,
+    ) -> bool;
+
+    pub fn stbi_write_png_to_func(
+        func: StbiWriteFunc,
+        context: *mut c_void,
+        w: c_int,
+        h: c_int,
+        comp: c_int,
+        data: *const u8,
+        stride_in_bytes: c_int,
+    ) -> bool;
+
+    pub fn stbi_write_bmp_to_func(
+        func: StbiWriteFunc,
+        context: *mut c_void,
+        w: c_int,
+        h: c_int,
+        comp: c_int,
         data: *const u8,
     ) -> bool;
+
+    pub fn stbi_write_tga_to_func(
+        func: StbiWriteFunc,
+        context: *mut c_void,
+        w: c_int,
+        h: c_int,
+        comp: c_int,
+        data: *const u8,
+    ) -> bool;
+
+    pub fn stbi_write_jpg_to_func(
+        func: StbiWriteFunc,
+        context: *mut c_void,
+        w: c_int,
+        h: c_int,
+        comp: c_int,
+        data: *const u8,
+        quality: c_int,
+    ) -> bool;
+
+    pub fn stbi_write_hdr_to_func(
+        func: StbiWriteFunc,
+        context: *mut c_void,
+        w: c_int,
+        h: c_int,
+        comp: c_int,
+        data: *const f32,
+    ) -> bool;
 }
@@ -3,11 +3,20 @@ use std::ffi::c_void;
 use super::context::DivisionContext;
 
 #[repr(i32)]
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
 pub enum DivisionTextureFormat {
     R8Uint = 1,
     RGB24Uint = 2,
     RGBA32Uint = 3,
+    RG16Uint = 4,
+    BGRA32Uint = 5,
+    RGBA32UintSrgb = 6,
+    BGRA32UintSrgb = 7,
+    R16Float = 8,
+    RGBA64Float = 9,
+    RGBA128Float = 10,
+    Depth32Float = 11,
+    Depth24Stencil8 = 12,
 }
 
 #[repr(i32)]
@@ -22,12 +31,31 @@ pub enum DivisionTextureChannelSwizzleVariant {
 }
 
 #[repr(i32)]
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
 pub enum DivisionTextureMinMagFilter {
     Nearest = 1,
     Linear = 2
 }
 
+#[repr(i32)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum DivisionTextureMipmapFilter {
+    None = 0,
+    Nearest = 1,
+    Linear = 2,
+}
+
+#[repr(i32)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum DivisionTextureWrapMode {
+    /// The sampling the core used before wrap modes could be set
+    Default = 0,
+    Repeat = 1,
+    MirroredRepeat = 2,
+    ClampToEdge = 3,
+    ClampToBorder = 4,
+}
+
 #[repr(C)]
 pub struct DivisionTextureChannelsSwizzle {
     pub red: DivisionTextureChannelSwizzleVariant,
@@ -45,6 +73,12 @@ pub struct DivisionTextureDescriptor {
     pub width: u32,
     pub height: u32,
     pub has_channels_swizzle: bool,
+    pub mipmap_filter: DivisionTextureMipmapFilter,
+    pub wrap_mode_u: DivisionTextureWrapMode,
+    pub wrap_mode_v: DivisionTextureWrapMode,
+    pub border_color: [f32; 4],
+    /// Levels above the first are generated from it by `division_engine_texture_set_data`
+    pub mip_level_count: u32,
 }
 
 extern "C" {
@@ -60,4 +94,16 @@ extern "C" {
         texture_id: u32,
         data: *const c_void,
     );
+
+    /// `row_stride` is the distance in bytes between the starts of rows in `data`
+    pub fn division_engine_texture_set_sub_data(
+        ctx: *mut DivisionContext,
+        texture_id: u32,
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32,
+        row_stride: u32,
+        data: *const c_void,
+    );
 }
\ No newline at end of file
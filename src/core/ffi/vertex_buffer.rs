@@ -3,6 +3,7 @@ use std::ffi::c_void;
 use super::{context::DivisionContext, shader::DivisionShaderVariableType};
 
 #[repr(i32)]
+#[derive(Clone, Copy)]
 pub enum DivisionRenderTopology {
     Triangles = 1,
     Points = 2,
@@ -10,6 +11,7 @@ pub enum DivisionRenderTopology {
 }
 
 #[repr(C)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
 pub struct DivisionVertexAttributeDescriptor {
     pub field_type: DivisionShaderVariableType,
     pub location: i32,
@@ -40,6 +42,14 @@ pub struct DivisionVertexBufferBorrowedData {
     pub instance_data_ptr: *mut c_void,
 }
 
+/// Part of the per-instance storage, `count` instances starting with `first`
+#[repr(C)]
+pub struct DivisionVertexBufferBorrowedRange {
+    pub data_ptr: *mut c_void,
+    pub first: u32,
+    pub count: u32,
+}
+
 extern "C" {
     pub fn division_engine_vertex_buffer_alloc(
         ctx: *mut DivisionContext,
@@ -47,6 +57,13 @@ extern "C" {
         out_vertex_buffer_id: *mut u32,
     ) -> bool;
 
+    /// Attribute arrays of the descriptor are owned by the vertex buffer
+    pub fn division_engine_vertex_buffer_get_descriptor(
+        ctx: *mut DivisionContext,
+        vertex_buffer_id: u32,
+        out_descriptor: *mut DivisionVertexBufferDescriptor,
+    ) -> bool;
+
     pub fn division_engine_vertex_buffer_free(
         ctx: *mut DivisionContext,
         vertex_buffer_id: u32,
@@ -64,6 +81,24 @@ extern "C" {
         data_pointer: *mut DivisionVertexBufferBorrowedData,
     );
 
+    pub fn division_engine_vertex_buffer_borrow_instance_range(
+        ctx: *mut DivisionContext,
+        vertex_buffer_id: u32,
+        first_instance: u32,
+        instance_count: u32,
+        out_borrowed_range: *mut DivisionVertexBufferBorrowedRange,
+    ) -> bool;
+
+    /// Uploads only `dirty_count` instances starting with `dirty_first`,
+    /// which is relative to the beginning of the borrowed range
+    pub fn division_engine_vertex_buffer_return_instance_range(
+        ctx: *mut DivisionContext,
+        vertex_buffer_id: u32,
+        borrowed_range: *const DivisionVertexBufferBorrowedRange,
+        dirty_first: u32,
+        dirty_count: u32,
+    );
+
     pub fn division_engine_vertex_buffer_resize(
         ctx: *mut DivisionContext,
         vertex_buffer: u32,
@@ -0,0 +1,42 @@
+use std::ffi::{c_float, c_int};
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum DivisionWindowEventType {
+    Resized = 1,
+    FocusGained = 2,
+    FocusLost = 3,
+    Minimized = 4,
+    Restored = 5,
+    CloseRequested = 6,
+    ContentScaleChanged = 7,
+}
+
+#[repr(C)]
+pub struct DivisionWindowEvent {
+    /// One of `DivisionWindowEventType`, kept as an integer since the core
+    /// may send types the bindings don't know
+    pub event_type: u32,
+    pub frame_buffer_width: c_int,
+    pub frame_buffer_height: c_int,
+    pub window_width: c_int,
+    pub window_height: c_int,
+    pub content_scale_x: c_float,
+    pub content_scale_y: c_float,
+}
+
+impl TryFrom<u32> for DivisionWindowEventType {
+    type Error = u32;
+
+    fn try_from(value: u32) -> Result<Self, Self::Error> {
+        Ok(match value {
+            1 => DivisionWindowEventType::Resized,
+            2 => DivisionWindowEventType::FocusGained,
+            3 => DivisionWindowEventType::FocusLost,
+            4 => DivisionWindowEventType::Minimized,
+            5 => DivisionWindowEventType::Restored,
+            6 => DivisionWindowEventType::CloseRequested,
+            7 => DivisionWindowEventType::ContentScaleChanged,
+            _ => return Err(value),
+        })
+    }
+}
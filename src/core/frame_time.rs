@@ -0,0 +1,148 @@
+use std::time::{Duration, Instant};
+
+#[derive(Clone, Copy, Debug)]
+pub struct FrameTime {
+    /// Time elapsed since the previous frame. Zero for the first frame
+    pub delta: Duration,
+    /// Time elapsed since the first frame
+    pub total: Duration,
+    pub frame_index: u64,
+    /// Position of the frame between the last and the next fixed update in `[0, 1)`.
+    /// Use it to interpolate the state simulated in `fixed_update`.
+    /// Always zero when the fixed timestep is disabled
+    pub interpolation_alpha: f32,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct FixedTime {
+    /// The fixed step duration
+    pub delta: Duration,
+    /// Simulated time, that is `delta * step_index`
+    pub total: Duration,
+    pub step_index: u64,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct FixedTimestep {
+    pub step: Duration,
+    /// Upper bound of fixed updates per frame. When a frame takes longer than
+    /// that many steps, the rest of the accumulated time is dropped instead of
+    /// making the next frames even slower
+    pub max_steps_per_frame: u32,
+}
+
+pub(crate) struct FrameTimer {
+    is_started: bool,
+    last_frame_instant: Option<Instant>,
+    frame_time: FrameTime,
+    fixed_timestep: Option<FixedTimestep>,
+    fixed_time: FixedTime,
+    accumulator: Duration,
+    fixed_steps_in_frame: u32,
+}
+
+impl FixedTimestep {
+    pub const DEFAULT_MAX_STEPS_PER_FRAME: u32 = 8;
+
+    pub fn new(step: Duration) -> FixedTimestep {
+        assert!(!step.is_zero(), "The fixed timestep must be positive");
+
+        FixedTimestep {
+            step,
+            max_steps_per_frame: Self::DEFAULT_MAX_STEPS_PER_FRAME,
+        }
+    }
+
+    pub fn with_max_steps_per_frame(mut self, max_steps_per_frame: u32) -> Self {
+        self.max_steps_per_frame = max_steps_per_frame;
+        self
+    }
+}
+
+impl FrameTimer {
+    pub fn new(fixed_timestep: Option<FixedTimestep>) -> FrameTimer {
+        FrameTimer {
+            is_started: false,
+            last_frame_instant: None,
+            frame_time: FrameTime {
+                delta: Duration::ZERO,
+                total: Duration::ZERO,
+                frame_index: 0,
+                interpolation_alpha: 0.,
+            },
+            fixed_time: FixedTime {
+                delta: fixed_timestep.map_or(Duration::ZERO, |f| f.step),
+                total: Duration::ZERO,
+                step_index: 0,
+            },
+            fixed_timestep,
+            accumulator: Duration::ZERO,
+            fixed_steps_in_frame: 0,
+        }
+    }
+
+    pub fn begin_frame(&mut self, now: Instant) {
+        let delta = self
+            .last_frame_instant
+            .map_or(Duration::ZERO, |last| now.saturating_duration_since(last));
+
+        self.last_frame_instant = Some(now);
+        self.begin_frame_with_delta(delta);
+    }
+
+    /// Starts a frame that took `delta`, regardless of the wall clock.
+    /// The delta of the first frame is ignored
+    pub fn begin_frame_with_delta(&mut self, delta: Duration) {
+        match self.is_started {
+            true => {
+                self.frame_time.delta = delta;
+                self.frame_time.frame_index += 1;
+            }
+            false => self.frame_time.delta = Duration::ZERO,
+        }
+
+        self.is_started = true;
+        self.frame_time.total += self.frame_time.delta;
+        self.accumulator += self.frame_time.delta;
+        self.fixed_steps_in_frame = 0;
+    }
+
+    /// Returns the next fixed step to simulate in the current frame, if any
+    pub fn next_fixed_step(&mut self) -> Option<FixedTime> {
+        let fixed_timestep = self.fixed_timestep?;
+
+        if self.accumulator < fixed_timestep.step {
+            return None;
+        }
+
+        if self.fixed_steps_in_frame >= fixed_timestep.max_steps_per_frame {
+            self.accumulator = Duration::from_nanos(
+                (self.accumulator.as_nanos() % fixed_timestep.step.as_nanos()) as u64,
+            );
+            return None;
+        }
+
+        let fixed_time = self.fixed_time;
+
+        self.accumulator -= fixed_timestep.step;
+        self.fixed_steps_in_frame += 1;
+        self.fixed_time.step_index += 1;
+        self.fixed_time.total += fixed_timestep.step;
+
+        Some(fixed_time)
+    }
+
+    pub fn frame_time(&self) -> FrameTime {
+        let interpolation_alpha = match self.fixed_timestep {
+            Some(f) => {
+                (self.accumulator.as_secs_f64() / f.step.as_secs_f64()).min(1.) as f32
+            }
+            None => 0.,
+        };
+
+        FrameTime {
+            interpolation_alpha,
+            ..self.frame_time
+        }
+    }
+}
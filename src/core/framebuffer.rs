@@ -0,0 +1,98 @@
+use division_math::Vector2;
+
+use super::{
+    context::Error, ffi::renderer::division_engine_renderer_read_framebuffer, Context,
+    Image,
+};
+
+pub const FRAMEBUFFER_READ_CHANNELS: usize = 4;
+
+impl Context {
+    /// Size of the framebuffer in pixels, that differs from the window size
+    /// on HiDPI displays. Tracked from the resize events, fails before the first one
+    pub fn framebuffer_size(&self) -> Result<Vector2, Error> {
+        self.runner_state().framebuffer_size.ok_or_else(|| {
+            Error::Core(String::from(
+                "The framebuffer size isn't known before the first resize event",
+            ))
+        })
+    }
+
+    /// Framebuffer pixels per window unit, above one on HiDPI displays.
+    /// Known after the first resize event, one before it
+    pub fn framebuffer_scale(&self) -> Vector2 {
+        let runner_state = self.runner_state();
+        match (runner_state.framebuffer_size, runner_state.window_size) {
+            (Some(framebuffer), Some(window)) if window.x > 0. && window.y > 0. => {
+                Vector2::new(framebuffer.x / window.x, framebuffer.y / window.y)
+            }
+            _ => Vector2::new(1., 1.),
+        }
+    }
+
+    /// Reads back the whole framebuffer presented by the last frame as an RGBA image
+    pub fn read_framebuffer(&mut self) -> Result<Image, Error> {
+        let size = self.framebuffer_size()?;
+        self.read_framebuffer_region(0, 0, size.x as usize, size.y as usize)
+    }
+
+    /// Reads back a region of the framebuffer as an RGBA image.
+    /// `x` and `y` are the bottom left corner of the region in pixels,
+    /// the same origin that the canvas uses. Image rows are ordered from top to bottom
+    pub fn read_framebuffer_region(
+        &mut self,
+        x: usize,
+        y: usize,
+        width: usize,
+        height: usize,
+    ) -> Result<Image, Error> {
+        check_framebuffer_region(x, y, width, height, self.framebuffer_size()?)?;
+
+        let mut pixels = vec![0u8; width * height * FRAMEBUFFER_READ_CHANNELS];
+        let ok = unsafe {
+            division_engine_renderer_read_framebuffer(
+                self,
+                x as u32,
+                y as u32,
+                width as u32,
+                height as u32,
+                pixels.as_mut_ptr(),
+            )
+        };
+
+        if !ok {
+            return Err(Error::Core("Failed to read the framebuffer".to_string()));
+        }
+
+        unsafe {
+            Ok(Image::create_from_raw_in_memory(
+                pixels,
+                width,
+                height,
+                FRAMEBUFFER_READ_CHANNELS,
+            ))
+        }
+    }
+}
+
+pub(crate) fn check_framebuffer_region(
+    x: usize,
+    y: usize,
+    width: usize,
+    height: usize,
+    framebuffer_size: Vector2,
+) -> Result<(), Error> {
+    let fits = |start: usize, len: usize, size: f32| {
+        start.checked_add(len).is_some_and(|end| end <= size as usize)
+    };
+
+    if fits(x, width, framebuffer_size.x) && fits(y, height, framebuffer_size.y) {
+        Ok(())
+    } else {
+        Err(Error::Core(format!(
+            "Framebuffer region (x: {x}, y: {y}, width: {width}, height: {height}) \
+             is out of the framebuffer bounds ({}x{})",
+            framebuffer_size.x, framebuffer_size.y
+        )))
+    }
+}
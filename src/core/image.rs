@@ -1,5 +1,5 @@
 use std::{
-    ffi::{c_int, CString},
+    ffi::{c_int, c_void, CString},
     mem::MaybeUninit,
     path::Path,
     slice,
@@ -9,7 +9,9 @@ use super::{
     context::Error,
     ffi::stb::{
         stbi_image_free, stbi_load, stbi_load_from_memory,
-        stbi_set_flip_vertically_on_load, stbi_write_jpg,
+        stbi_set_flip_vertically_on_load, stbi_write_bmp, stbi_write_bmp_to_func,
+        stbi_write_hdr, stbi_write_hdr_to_func, stbi_write_jpg, stbi_write_jpg_to_func,
+        stbi_write_png, stbi_write_png_to_func, stbi_write_tga, stbi_write_tga_to_func,
     },
     TextureFormat,
 };
@@ -31,6 +33,15 @@ pub struct ImageSettings {
     vertical_flip: bool,
 }
 
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum ImageFileFormat {
+    Png,
+    Bmp,
+    Tga,
+    Jpg { quality: u32 },
+    Hdr,
+}
+
 impl Image {
     pub unsafe fn create_from_raw_in_memory(
         buffer: Vec<u8>,
@@ -111,8 +122,74 @@ impl Image {
         }
     }
 
+    /// Writes the image with the encoder picked by the `path` extension
+    pub fn write_to_file_by_extension(&self, path: &Path) -> Result<(), Error> {
+        let format = ImageFileFormat::from_path(path).ok_or_else(|| {
+            Error::Core(format!(
+                "Unknown image file extension of the path: {path:?}"
+            ))
+        })?;
+
+        self.write_to_file(path, format)
+    }
+
+    pub fn write_to_file(
+        &self,
+        path: &Path,
+        format: ImageFileFormat,
+    ) -> Result<(), Error> {
+        let path = path
+            .to_str()
+            .ok_or_else(|| Error::Core("The image path is incorrect".to_string()))?;
+        let c_str = CString::new(path)
+            .map_err(|_| Error::Core("The image path contains a nul byte".to_string()))?;
+        let (w, h, comp) = self.c_dimensions();
+
+        let result = unsafe {
+            match format {
+                ImageFileFormat::Png => stbi_write_png(
+                    c_str.as_ptr(),
+                    w,
+                    h,
+                    comp,
+                    self.data().as_ptr(),
+                    self.row_stride() as c_int,
+                ),
+                ImageFileFormat::Bmp => {
+                    stbi_write_bmp(c_str.as_ptr(), w, h, comp, self.data().as_ptr())
+                }
+                ImageFileFormat::Tga => {
+                    stbi_write_tga(c_str.as_ptr(), w, h, comp, self.data().as_ptr())
+                }
+                ImageFileFormat::Jpg { quality } => stbi_write_jpg(
+                    c_str.as_ptr(),
+                    w,
+                    h,
+                    comp,
+                    self.data().as_ptr(),
+                    quality as c_int,
+                ),
+                ImageFileFormat::Hdr => {
+                    let hdr_data = self.to_hdr_data();
+                    stbi_write_hdr(c_str.as_ptr(), w, h, comp, hdr_data.as_ptr())
+                }
+            }
+        };
+
+        if result {
+            Ok(())
+        } else {
+            Err(Error::Core("Failed to write an image to file".to_string()))
+        }
+    }
+
     pub fn write_to_file_jpg(&self, path: &Path) -> Result<(), Error> {
-        self.write_to_file_jpg_with_quality(path, 80)
+        self.write_to_file(
+            path,
+            ImageFileFormat::Jpg {
+                quality: ImageFileFormat::DEFAULT_JPG_QUALITY,
+            },
+        )
     }
 
     pub fn write_to_file_jpg_with_quality(
@@ -120,22 +197,69 @@ impl Image {
         path: &Path,
         quality: u32,
     ) -> Result<(), Error> {
-        let c_str = CString::new(path.to_str().unwrap()).unwrap();
+        self.write_to_file(path, ImageFileFormat::Jpg { quality })
+    }
+
+    /// Encodes the image in memory, the same way as it would be written to a file
+    pub fn encode_to_vec(&self, format: ImageFileFormat) -> Result<Vec<u8>, Error> {
+        let mut buffer = Vec::<u8>::new();
+        let ctx = &mut buffer as *mut Vec<u8> as *mut c_void;
+        let (w, h, comp) = self.c_dimensions();
+
         let result = unsafe {
-            stbi_write_jpg(
-                c_str.as_ptr(),
-                self.width as c_int,
-                self.height as c_int,
-                self.channels as c_int,
-                self.data().as_ptr(),
-                quality as c_int,
-            )
+            match format {
+                ImageFileFormat::Png => stbi_write_png_to_func(
+                    write_to_vec_callback,
+                    ctx,
+                    w,
+                    h,
+                    comp,
+                    self.data().as_ptr(),
+                    self.row_stride() as c_int,
+                ),
+                ImageFileFormat::Bmp => stbi_write_bmp_to_func(
+                    write_to_vec_callback,
+                    ctx,
+                    w,
+                    h,
+                    comp,
+                    self.data().as_ptr(),
+                ),
+                ImageFileFormat::Tga => stbi_write_tga_to_func(
+                    write_to_vec_callback,
+                    ctx,
+                    w,
+                    h,
+                    comp,
+                    self.data().as_ptr(),
+                ),
+                ImageFileFormat::Jpg { quality } => stbi_write_jpg_to_func(
+                    write_to_vec_callback,
+                    ctx,
+                    w,
+                    h,
+                    comp,
+                    self.data().as_ptr(),
+                    quality as c_int,
+                ),
+                ImageFileFormat::Hdr => {
+                    let hdr_data = self.to_hdr_data();
+                    stbi_write_hdr_to_func(
+                        write_to_vec_callback,
+                        ctx,
+                        w,
+                        h,
+                        comp,
+                        hdr_data.as_ptr(),
+                    )
+                }
+            }
         };
 
         if result {
-            Ok(())
+            Ok(buffer)
         } else {
-            Err(Error::Core("Failed to write an image to file".to_string()))
+            Err(Error::Core("Failed to encode an image".to_string()))
         }
     }
 
@@ -172,6 +296,42 @@ impl Image {
     pub fn height(&self) -> usize {
         self.height
     }
+
+    #[inline]
+    pub fn row_stride(&self) -> usize {
+        self.width * self.channels
+    }
+
+    #[inline]
+    fn c_dimensions(&self) -> (c_int, c_int, c_int) {
+        (
+            self.width as c_int,
+            self.height as c_int,
+            self.channels as c_int,
+        )
+    }
+
+    fn to_hdr_data(&self) -> Vec<f32> {
+        self.data().iter().map(|v| *v as f32 / 255.).collect()
+    }
+}
+
+impl ImageFileFormat {
+    pub const DEFAULT_JPG_QUALITY: u32 = 80;
+
+    pub fn from_path(path: &Path) -> Option<ImageFileFormat> {
+        let extension = path.extension()?.to_str()?.to_ascii_lowercase();
+        Some(match extension.as_str() {
+            "png" => ImageFileFormat::Png,
+            "bmp" => ImageFileFormat::Bmp,
+            "tga" => ImageFileFormat::Tga,
+            "jpg" | "jpeg" => ImageFileFormat::Jpg {
+                quality: Self::DEFAULT_JPG_QUALITY,
+            },
+            "hdr" => ImageFileFormat::Hdr,
+            _ => return None,
+        })
+    }
 }
 
 impl Drop for Image {
@@ -183,18 +343,28 @@ impl Drop for Image {
 }
 
 impl ImageSettings {
+    /// # Panics
+    /// If images can't be loaded as the format, see `try_new`
     pub fn new(texture_format: TextureFormat, vertical_flip: bool) -> ImageSettings {
-        ImageSettings {
-            channels: texture_format_to_channels(texture_format),
+        ImageSettings::try_new(texture_format, vertical_flip)
+            .unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    /// Fails if images can't be loaded as the format, see `image_channel_count`
+    pub fn try_new(
+        texture_format: TextureFormat,
+        vertical_flip: bool,
+    ) -> Result<ImageSettings, Error> {
+        Ok(ImageSettings {
+            channels: texture_format_to_channels(texture_format)?,
             vertical_flip,
-        }
+        })
     }
 
+    /// # Panics
+    /// If images can't be loaded as the format, see `try_new`
     pub fn with_texture_format(texture_format: TextureFormat) -> ImageSettings {
-        ImageSettings {
-            channels: texture_format_to_channels(texture_format),
-            vertical_flip: false,
-        }
+        ImageSettings::new(texture_format, false)
     }
 
     pub fn with_vertical_flip(vertical_flip: bool) -> ImageSettings {
@@ -214,10 +384,21 @@ impl Default for ImageSettings {
     }
 }
 
-fn texture_format_to_channels(texture_format: TextureFormat) -> c_int {
-    match texture_format {
-        TextureFormat::R8Uint => 1,
-        TextureFormat::RGB24Uint => 3,
-        TextureFormat::RGBA32Uint => 4,
+unsafe extern "C" fn write_to_vec_callback(
+    context: *mut c_void,
+    data: *mut c_void,
+    size: c_int,
+) {
+    let buffer = &mut *(context as *mut Vec<u8>);
+    buffer.extend_from_slice(slice::from_raw_parts(data as *const u8, size as usize));
+}
+
+fn texture_format_to_channels(texture_format: TextureFormat) -> Result<c_int, Error> {
+    match texture_format.image_channel_count() {
+        Some(channels) => Ok(channels as c_int),
+        None => Err(Error::Core(format!(
+            "Images can't be loaded as {texture_format:?}, \
+             it isn't 8 bits per channel in RGBA order"
+        ))),
     }
 }
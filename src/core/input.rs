@@ -1,15 +1,274 @@
-use std::mem::MaybeUninit;
+use std::{mem::MaybeUninit, time::Duration};
 
-use super::{Context, ffi::input::{division_engine_input_get_input, DivisionInput}};
+use division_math::Vector2;
 
-pub use super::ffi::{input::MouseButton, keycode::Keycode};
+use super::{
+    ffi::input::{
+        division_engine_input_get_input, DivisionInput, DivisionKeyboardInput,
+        DivisionMouseInput, DivisionTextInput,
+    },
+    Context,
+};
+
+pub use super::ffi::{
+    input::{DivisionScrollDeltaUnit as ScrollDeltaUnit, MouseButton},
+    keycode::{Keycode, ParseInputNameError},
+};
+
+const KEY_MASK_COUNT: usize = 4;
+const KEY_SLOT_COUNT: usize = KEY_MASK_COUNT * 32;
+const MOUSE_BUTTON_SLOT_COUNT: usize = 32;
+
+/// Input tracked between frames by the runner.
+/// Unlike the raw `DivisionInput` snapshot it knows what has changed since
+/// the previous frame
+pub struct InputState {
+    current: DivisionInput,
+    previous: DivisionInput,
+    key_pressed_at: [Duration; KEY_SLOT_COUNT],
+    mouse_button_pressed_at: [Duration; MOUSE_BUTTON_SLOT_COUNT],
+    text_input: Vec<char>,
+    time: Duration,
+    is_updated: bool,
+}
+
+#[derive(Clone, Copy)]
+pub struct ScrollDelta {
+    pub delta: Vector2,
+    pub unit: ScrollDeltaUnit,
+}
 
 impl Context {
+    /// Returns the replayed frame instead of the live input
+    /// while `CoreRunner::replay_input` is running
     pub fn get_input(&mut self) -> DivisionInput {
+        if let Some(input) = self.runner_state().replayed_input() {
+            return input;
+        }
+
         let mut input = MaybeUninit::uninit();
         unsafe {
             division_engine_input_get_input(self, input.as_mut_ptr());
             input.assume_init()
         }
     }
-}
\ No newline at end of file
+
+    pub fn input_state(&self) -> &InputState {
+        &self.runner_state().input_state
+    }
+}
+
+impl InputState {
+    pub(crate) fn new() -> InputState {
+        InputState {
+            current: empty_input(0, 0),
+            previous: empty_input(0, 0),
+            key_pressed_at: [Duration::ZERO; KEY_SLOT_COUNT],
+            mouse_button_pressed_at: [Duration::ZERO; MOUSE_BUTTON_SLOT_COUNT],
+            text_input: Vec::new(),
+            time: Duration::ZERO,
+            is_updated: false,
+        }
+    }
+
+    /// Advances the state to the next frame. `time` is the total time of the frame
+    pub(crate) fn update(&mut self, input: &DivisionInput, time: Duration) {
+        // Nothing is pressed before the first frame, and the mouse doesn't move
+        self.previous = match self.is_updated {
+            true => self.current,
+            false => empty_input(input.mouse.pos_x, input.mouse.pos_y),
+        };
+        self.current = *input;
+        self.is_updated = true;
+        self.time = time;
+
+        // The text input buffer is owned by the core and is valid only until
+        // the next poll, so keep a copy
+        self.text_input.clear();
+        self.text_input.extend(input.text.characters());
+        self.current.text = DivisionTextInput {
+            characters: std::ptr::null(),
+            character_count: 0,
+        };
+
+        for slot in 0..KEY_SLOT_COUNT {
+            if key_mask_bit(&self.current.keyboard, slot)
+                && !key_mask_bit(&self.previous.keyboard, slot)
+            {
+                self.key_pressed_at[slot] = time;
+            }
+        }
+
+        for slot in 0..MOUSE_BUTTON_SLOT_COUNT {
+            if mouse_mask_bit(&self.current.mouse, slot)
+                && !mouse_mask_bit(&self.previous.mouse, slot)
+            {
+                self.mouse_button_pressed_at[slot] = time;
+            }
+        }
+    }
+
+    #[inline]
+    pub fn raw(&self) -> &DivisionInput {
+        &self.current
+    }
+
+    #[inline]
+    pub fn is_key_down(&self, keycode: Keycode) -> bool {
+        self.current.keyboard.is_key_pressed(keycode)
+    }
+
+    #[inline]
+    pub fn just_pressed(&self, keycode: Keycode) -> bool {
+        self.current.keyboard.is_key_pressed(keycode)
+            && !self.previous.keyboard.is_key_pressed(keycode)
+    }
+
+    #[inline]
+    pub fn just_released(&self, keycode: Keycode) -> bool {
+        !self.current.keyboard.is_key_pressed(keycode)
+            && self.previous.keyboard.is_key_pressed(keycode)
+    }
+
+    /// Returns `true` if the OS sent a key repeat for the held key this frame
+    #[inline]
+    pub fn is_key_repeated(&self, keycode: Keycode) -> bool {
+        self.current.keyboard.is_key_repeated(keycode)
+    }
+
+    /// Handy for text editing and navigation, where holding a key should
+    /// repeat the action the same way as typing does
+    #[inline]
+    pub fn just_pressed_or_repeated(&self, keycode: Keycode) -> bool {
+        self.just_pressed(keycode) || self.is_key_repeated(keycode)
+    }
+
+    /// Returns how long the key is held down, or `None` if it isn't pressed
+    pub fn held_duration(&self, keycode: Keycode) -> Option<Duration> {
+        match self.is_key_down(keycode) {
+            true => Some(self.time - self.key_pressed_at[keycode as usize]),
+            false => None,
+        }
+    }
+
+    /// Iterates over all keys that are currently held down
+    pub fn keys_down(&self) -> impl Iterator<Item = Keycode> + '_ {
+        (0..KEY_SLOT_COUNT)
+            .filter(|slot| key_mask_bit(&self.current.keyboard, *slot))
+            .filter_map(|slot| Keycode::from_u32(slot as u32))
+    }
+
+    #[inline]
+    pub fn is_button_down(&self, mouse_button: MouseButton) -> bool {
+        self.current.mouse.is_button_pressed(mouse_button)
+    }
+
+    #[inline]
+    pub fn button_just_pressed(&self, mouse_button: MouseButton) -> bool {
+        self.current.mouse.is_button_pressed(mouse_button)
+            && !self.previous.mouse.is_button_pressed(mouse_button)
+    }
+
+    #[inline]
+    pub fn button_just_released(&self, mouse_button: MouseButton) -> bool {
+        !self.current.mouse.is_button_pressed(mouse_button)
+            && self.previous.mouse.is_button_pressed(mouse_button)
+    }
+
+    /// Returns how long the mouse button is held down, or `None` if it isn't pressed
+    pub fn button_held_duration(&self, mouse_button: MouseButton) -> Option<Duration> {
+        if !self.is_button_down(mouse_button) {
+            return None;
+        }
+
+        let slot: u32 = mouse_button.into();
+        let pressed_at = self.mouse_button_pressed_at.get(slot as usize)?;
+        Some(self.time - *pressed_at)
+    }
+
+    #[inline]
+    pub fn mouse_position(&self) -> Vector2 {
+        Vector2::new(
+            self.current.mouse.pos_x as f32,
+            self.current.mouse.pos_y as f32,
+        )
+    }
+
+    #[inline]
+    pub fn scroll_delta(&self) -> ScrollDelta {
+        ScrollDelta {
+            delta: Vector2::new(
+                self.current.mouse.scroll_delta_x,
+                self.current.mouse.scroll_delta_y,
+            ),
+            // Unknown units are treated as lines, the platform default
+            unit: self
+                .current
+                .mouse
+                .scroll_unit()
+                .unwrap_or(ScrollDeltaUnit::Line),
+        }
+    }
+
+    /// Characters typed since the previous frame, in the order they were entered
+    #[inline]
+    pub fn text_input(&self) -> &[char] {
+        &self.text_input
+    }
+
+    /// Mouse movement since the previous frame
+    #[inline]
+    pub fn mouse_delta(&self) -> Vector2 {
+        Vector2::new(
+            (self.current.mouse.pos_x - self.previous.mouse.pos_x) as f32,
+            (self.current.mouse.pos_y - self.previous.mouse.pos_y) as f32,
+        )
+    }
+}
+
+impl ScrollDelta {
+    pub fn in_pixels(&self, line_height: f32) -> Vector2 {
+        match self.unit {
+            ScrollDeltaUnit::Pixel => self.delta,
+            ScrollDeltaUnit::Line => self.delta * line_height,
+        }
+    }
+
+    pub fn in_lines(&self, line_height: f32) -> Vector2 {
+        match self.unit {
+            ScrollDeltaUnit::Pixel => self.delta * (1. / line_height),
+            ScrollDeltaUnit::Line => self.delta,
+        }
+    }
+}
+
+pub(crate) fn empty_input(mouse_pos_x: i32, mouse_pos_y: i32) -> DivisionInput {
+    DivisionInput {
+        mouse: DivisionMouseInput {
+            pos_x: mouse_pos_x,
+            pos_y: mouse_pos_y,
+            mouse_button_state_mask: 0,
+            scroll_delta_x: 0.,
+            scroll_delta_y: 0.,
+            scroll_delta_unit: ScrollDeltaUnit::Line.into(),
+        },
+        keyboard: DivisionKeyboardInput {
+            key_state_mask: [0; KEY_MASK_COUNT],
+            key_repeat_mask: [0; KEY_MASK_COUNT],
+        },
+        text: DivisionTextInput {
+            characters: std::ptr::null(),
+            character_count: 0,
+        },
+    }
+}
+
+#[inline]
+fn key_mask_bit(keyboard: &DivisionKeyboardInput, slot: usize) -> bool {
+    (keyboard.key_state_mask[slot / 32] & (1 << (slot % 32))) != 0
+}
+
+#[inline]
+fn mouse_mask_bit(mouse: &DivisionMouseInput, slot: usize) -> bool {
+    (mouse.mouse_button_state_mask & (1 << slot)) != 0
+}
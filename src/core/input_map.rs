@@ -0,0 +1,639 @@
+use std::{fmt::Display, fs, io, path::Path, str::FromStr};
+
+use super::input::{InputState, Keycode, MouseButton, ParseInputNameError};
+
+/// Maps named actions and axes to keys, mouse buttons and modifier chords,
+/// so game code doesn't depend on concrete bindings.
+///
+/// The config format is line based, `#` starts a comment:
+/// ```text
+/// action jump = Space, Mouse:Left
+/// action save = LCmdLCtrl+S
+/// axis move_x = D | A, RightArrow | LeftArrow
+/// ```
+/// Bindings are separated by commas, chord keys are joined with `+`
+/// (modifiers first), axis bindings are `positive | negative` pairs
+pub struct InputMap {
+    actions: Vec<Action>,
+    axes: Vec<Axis>,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum InputTrigger {
+    Key(Keycode),
+    MouseButton(MouseButton),
+}
+
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct Binding {
+    /// Sorted and without duplicates, so chords compare equal
+    /// regardless of the order the modifiers were given in
+    modifiers: Vec<Keycode>,
+    pub trigger: InputTrigger,
+}
+
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct AxisBinding {
+    pub positive: Binding,
+    pub negative: Binding,
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct BindingConflict {
+    pub binding: Binding,
+    pub used_by: Vec<String>,
+}
+
+#[derive(Debug)]
+pub enum Error {
+    Parse { line: usize, message: String },
+    UnknownAction(String),
+    UnknownAxis(String),
+    BindingNotFound(Binding),
+    AxisBindingNotFound(AxisBinding),
+    Conflict(BindingConflict),
+    Io(io::Error),
+}
+
+struct Action {
+    name: String,
+    bindings: Vec<Binding>,
+}
+
+struct Axis {
+    name: String,
+    bindings: Vec<AxisBinding>,
+}
+
+const MOUSE_PREFIX: &str = "Mouse:";
+
+impl InputMap {
+    pub fn new() -> InputMap {
+        InputMap {
+            actions: Vec::new(),
+            axes: Vec::new(),
+        }
+    }
+
+    pub fn load_from_file(path: &Path) -> Result<InputMap, Error> {
+        Self::from_config_str(&fs::read_to_string(path)?)
+    }
+
+    pub fn save_to_file(&self, path: &Path) -> Result<(), Error> {
+        Ok(fs::write(path, self.to_config_string())?)
+    }
+
+    pub fn from_config_str(config: &str) -> Result<InputMap, Error> {
+        let mut map = InputMap::new();
+
+        for (line_index, line) in config.lines().enumerate() {
+            let line_number = line_index + 1;
+            let parse_error = |message: String| Error::Parse {
+                line: line_number,
+                message,
+            };
+
+            let line = match line.find('#') {
+                Some(i) => &line[..i],
+                None => line,
+            }
+            .trim();
+
+            if line.is_empty() {
+                continue;
+            }
+
+            let (declaration, bindings) = line
+                .split_once('=')
+                .ok_or_else(|| parse_error("Expected `=`".to_string()))?;
+            let (kind, name) = declaration
+                .trim()
+                .split_once(char::is_whitespace)
+                .ok_or_else(|| parse_error("Expected `action` or `axis`".to_string()))?;
+            let name = name.trim();
+            let bindings = bindings
+                .split(',')
+                .map(str::trim)
+                .filter(|b| !b.is_empty());
+
+            match kind {
+                "action" => {
+                    map.add_action(name);
+                    for b in bindings {
+                        let binding = b.parse().map_err(|e: ParseInputNameError| {
+                            parse_error(e.to_string())
+                        })?;
+                        map.bind_action(name, binding);
+                    }
+                }
+                "axis" => {
+                    map.add_axis(name);
+                    for b in bindings {
+                        let binding = b.parse().map_err(|e: ParseInputNameError| {
+                            parse_error(e.to_string())
+                        })?;
+                        map.bind_axis(name, binding);
+                    }
+                }
+                k => return Err(parse_error(format!("Unknown declaration `{k}`"))),
+            }
+        }
+
+        Ok(map)
+    }
+
+    pub fn to_config_string(&self) -> String {
+        let mut config = String::new();
+
+        for action in &self.actions {
+            config.push_str(&format!(
+                "action {} = {}\n",
+                action.name,
+                join_to_string(&action.bindings)
+            ));
+        }
+
+        for axis in &self.axes {
+            config.push_str(&format!(
+                "axis {} = {}\n",
+                axis.name,
+                join_to_string(&axis.bindings)
+            ));
+        }
+
+        config
+    }
+
+    /// Declares the action without bindings, if it doesn't exist yet
+    pub fn add_action(&mut self, name: &str) {
+        if self.find_action(name).is_none() {
+            self.actions.push(Action {
+                name: name.to_string(),
+                bindings: Vec::new(),
+            });
+        }
+    }
+
+    /// Declares the axis without bindings, if it doesn't exist yet
+    pub fn add_axis(&mut self, name: &str) {
+        if self.find_axis(name).is_none() {
+            self.axes.push(Axis {
+                name: name.to_string(),
+                bindings: Vec::new(),
+            });
+        }
+    }
+
+    pub fn bind_action(&mut self, name: &str, binding: Binding) {
+        self.add_action(name);
+        let action = self.find_action_mut(name).unwrap();
+        if !action.bindings.contains(&binding) {
+            action.bindings.push(binding);
+        }
+    }
+
+    pub fn bind_axis(&mut self, name: &str, binding: AxisBinding) {
+        self.add_axis(name);
+        let axis = self.find_axis_mut(name).unwrap();
+        if !axis.bindings.contains(&binding) {
+            axis.bindings.push(binding);
+        }
+    }
+
+    pub fn unbind_action(&mut self, name: &str, binding: &Binding) -> Result<(), Error> {
+        let action = self
+            .find_action_mut(name)
+            .ok_or_else(|| Error::UnknownAction(name.to_string()))?;
+        let index = action
+            .bindings
+            .iter()
+            .position(|b| b == binding)
+            .ok_or_else(|| Error::BindingNotFound(binding.clone()))?;
+
+        action.bindings.remove(index);
+        Ok(())
+    }
+
+    pub fn unbind_axis(
+        &mut self,
+        name: &str,
+        binding: &AxisBinding,
+    ) -> Result<(), Error> {
+        let axis = self
+            .find_axis_mut(name)
+            .ok_or_else(|| Error::UnknownAxis(name.to_string()))?;
+        let index = axis
+            .bindings
+            .iter()
+            .position(|b| b == binding)
+            .ok_or_else(|| Error::AxisBindingNotFound(binding.clone()))?;
+
+        axis.bindings.remove(index);
+        Ok(())
+    }
+
+    /// Replaces the `old` binding of the action with the `new` one.
+    /// Fails if the new binding is already used by another action or axis
+    pub fn rebind_action(
+        &mut self,
+        name: &str,
+        old: &Binding,
+        new: Binding,
+    ) -> Result<(), Error> {
+        let action_index = self
+            .actions
+            .iter()
+            .position(|a| a.name == name)
+            .ok_or_else(|| Error::UnknownAction(name.to_string()))?;
+
+        let used_by = self
+            .action_users(&new, Some(action_index))
+            .chain(self.axis_users(&new, None))
+            .collect::<Vec<String>>();
+        if !used_by.is_empty() {
+            return Err(Error::Conflict(BindingConflict {
+                binding: new,
+                used_by,
+            }));
+        }
+
+        let old_binding = self.actions[action_index]
+            .bindings
+            .iter_mut()
+            .find(|b| *b == old)
+            .ok_or_else(|| Error::BindingNotFound(old.clone()))?;
+
+        *old_binding = new;
+        Ok(())
+    }
+
+    /// Replaces the `old` binding of the axis with the `new` one.
+    /// Fails if either direction of the new binding is already used
+    /// by an action or another axis
+    pub fn rebind_axis(
+        &mut self,
+        name: &str,
+        old: &AxisBinding,
+        new: AxisBinding,
+    ) -> Result<(), Error> {
+        let axis_index = self
+            .axes
+            .iter()
+            .position(|a| a.name == name)
+            .ok_or_else(|| Error::UnknownAxis(name.to_string()))?;
+
+        for binding in [&new.positive, &new.negative] {
+            let used_by = self
+                .action_users(binding, None)
+                .chain(self.axis_users(binding, Some(axis_index)))
+                .collect::<Vec<String>>();
+            if !used_by.is_empty() {
+                return Err(Error::Conflict(BindingConflict {
+                    binding: binding.clone(),
+                    used_by,
+                }));
+            }
+        }
+
+        let old_binding = self.axes[axis_index]
+            .bindings
+            .iter_mut()
+            .find(|b| *b == old)
+            .ok_or_else(|| Error::AxisBindingNotFound(old.clone()))?;
+
+        *old_binding = new;
+        Ok(())
+    }
+
+    pub fn clear_action(&mut self, name: &str) -> Result<(), Error> {
+        self.find_action_mut(name)
+            .ok_or_else(|| Error::UnknownAction(name.to_string()))?
+            .bindings
+            .clear();
+        Ok(())
+    }
+
+    pub fn clear_axis(&mut self, name: &str) -> Result<(), Error> {
+        self.find_axis_mut(name)
+            .ok_or_else(|| Error::UnknownAxis(name.to_string()))?
+            .bindings
+            .clear();
+        Ok(())
+    }
+
+    pub fn action_bindings(&self, name: &str) -> Option<&[Binding]> {
+        self.find_action(name).map(|a| a.bindings.as_slice())
+    }
+
+    pub fn axis_bindings(&self, name: &str) -> Option<&[AxisBinding]> {
+        self.find_axis(name).map(|a| a.bindings.as_slice())
+    }
+
+    /// Names of the actions and axes that use the binding
+    pub fn binding_users(&self, binding: &Binding) -> Vec<String> {
+        self.action_users(binding, None)
+            .chain(self.axis_users(binding, None))
+            .collect()
+    }
+
+    /// Every binding that is used by more than one action or axis
+    pub fn conflicts(&self) -> Vec<BindingConflict> {
+        let all_bindings = self
+            .actions
+            .iter()
+            .flat_map(|a| a.bindings.iter())
+            .chain(
+                self.axes
+                    .iter()
+                    .flat_map(|a| a.bindings.iter())
+                    .flat_map(|b| [&b.positive, &b.negative]),
+            );
+
+        let mut conflicts: Vec<BindingConflict> = Vec::new();
+        for binding in all_bindings {
+            if conflicts.iter().any(|c| c.binding == *binding) {
+                continue;
+            }
+
+            let used_by = self.binding_users(binding);
+            if used_by.len() > 1 {
+                conflicts.push(BindingConflict {
+                    binding: binding.clone(),
+                    used_by,
+                });
+            }
+        }
+
+        conflicts
+    }
+
+    pub fn is_action_down(&self, name: &str, input: &InputState) -> bool {
+        self.find_action(name)
+            .is_some_and(|a| a.bindings.iter().any(|b| b.is_down(input)))
+    }
+
+    pub fn action_just_pressed(&self, name: &str, input: &InputState) -> bool {
+        self.find_action(name)
+            .is_some_and(|a| a.bindings.iter().any(|b| b.just_pressed(input)))
+    }
+
+    pub fn action_just_released(&self, name: &str, input: &InputState) -> bool {
+        self.find_action(name)
+            .is_some_and(|a| a.bindings.iter().any(|b| b.just_released(input)))
+    }
+
+    /// Returns the axis value in `[-1, 1]`, or zero for an unknown axis
+    pub fn axis_value(&self, name: &str, input: &InputState) -> f32 {
+        let axis = match self.find_axis(name) {
+            Some(a) => a,
+            None => return 0.,
+        };
+
+        let value = axis.bindings.iter().fold(0., |value, b| {
+            value + b.positive.is_down(input) as i32 as f32
+                - b.negative.is_down(input) as i32 as f32
+        });
+
+        value.clamp(-1., 1.)
+    }
+
+    fn action_users<'a>(
+        &'a self,
+        binding: &'a Binding,
+        skipped_index: Option<usize>,
+    ) -> impl Iterator<Item = String> + 'a {
+        self.actions
+            .iter()
+            .enumerate()
+            .filter(move |(i, a)| {
+                Some(*i) != skipped_index && a.bindings.contains(binding)
+            })
+            .map(|(_, a)| a.name.clone())
+    }
+
+    fn axis_users<'a>(
+        &'a self,
+        binding: &'a Binding,
+        skipped_index: Option<usize>,
+    ) -> impl Iterator<Item = String> + 'a {
+        self.axes
+            .iter()
+            .enumerate()
+            .filter(move |(i, a)| {
+                Some(*i) != skipped_index
+                    && a.bindings
+                        .iter()
+                        .any(|b| b.positive == *binding || b.negative == *binding)
+            })
+            .map(|(_, a)| a.name.clone())
+    }
+
+    fn find_action(&self, name: &str) -> Option<&Action> {
+        self.actions.iter().find(|a| a.name == name)
+    }
+
+    fn find_action_mut(&mut self, name: &str) -> Option<&mut Action> {
+        self.actions.iter_mut().find(|a| a.name == name)
+    }
+
+    fn find_axis(&self, name: &str) -> Option<&Axis> {
+        self.axes.iter().find(|a| a.name == name)
+    }
+
+    fn find_axis_mut(&mut self, name: &str) -> Option<&mut Axis> {
+        self.axes.iter_mut().find(|a| a.name == name)
+    }
+}
+
+impl Binding {
+    /// Modifiers may come in any order and repeat
+    pub fn new(
+        modifiers: impl IntoIterator<Item = Keycode>,
+        trigger: InputTrigger,
+    ) -> Binding {
+        let mut modifiers: Vec<Keycode> = modifiers.into_iter().collect();
+        normalize_modifiers(&mut modifiers);
+
+        Binding { modifiers, trigger }
+    }
+
+    pub fn key(keycode: Keycode) -> Binding {
+        Binding {
+            modifiers: Vec::new(),
+            trigger: InputTrigger::Key(keycode),
+        }
+    }
+
+    pub fn mouse_button(mouse_button: MouseButton) -> Binding {
+        Binding {
+            modifiers: Vec::new(),
+            trigger: InputTrigger::MouseButton(mouse_button),
+        }
+    }
+
+    pub fn with_modifier(mut self, modifier: Keycode) -> Binding {
+        self.modifiers.push(modifier);
+        normalize_modifiers(&mut self.modifiers);
+        self
+    }
+
+    pub fn modifiers(&self) -> &[Keycode] {
+        &self.modifiers
+    }
+
+    pub fn is_down(&self, input: &InputState) -> bool {
+        self.modifiers_down(input)
+            && match self.trigger {
+                InputTrigger::Key(k) => input.is_key_down(k),
+                InputTrigger::MouseButton(b) => input.is_button_down(b),
+            }
+    }
+
+    pub fn just_pressed(&self, input: &InputState) -> bool {
+        self.modifiers_down(input)
+            && match self.trigger {
+                InputTrigger::Key(k) => input.just_pressed(k),
+                InputTrigger::MouseButton(b) => input.button_just_pressed(b),
+            }
+    }
+
+    pub fn just_released(&self, input: &InputState) -> bool {
+        match self.trigger {
+            InputTrigger::Key(k) => input.just_released(k),
+            InputTrigger::MouseButton(b) => input.button_just_released(b),
+        }
+    }
+
+    fn modifiers_down(&self, input: &InputState) -> bool {
+        self.modifiers.iter().all(|m| input.is_key_down(*m))
+    }
+}
+
+impl AxisBinding {
+    pub fn new(positive: Binding, negative: Binding) -> AxisBinding {
+        AxisBinding { positive, negative }
+    }
+}
+
+impl Default for InputMap {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Display for InputTrigger {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            InputTrigger::Key(k) => write!(f, "{k}"),
+            InputTrigger::MouseButton(b) => write!(f, "{MOUSE_PREFIX}{b}"),
+        }
+    }
+}
+
+impl FromStr for InputTrigger {
+    type Err = ParseInputNameError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.strip_prefix(MOUSE_PREFIX) {
+            Some(button) => Ok(InputTrigger::MouseButton(button.parse()?)),
+            None => Ok(InputTrigger::Key(s.parse()?)),
+        }
+    }
+}
+
+impl Display for Binding {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for m in &self.modifiers {
+            write!(f, "{m}+")?;
+        }
+        write!(f, "{}", self.trigger)
+    }
+}
+
+impl FromStr for Binding {
+    type Err = ParseInputNameError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = s.split('+').map(str::trim).collect::<Vec<&str>>();
+        let trigger = parts.pop().unwrap_or_default().parse()?;
+        let modifiers = parts
+            .into_iter()
+            .map(Keycode::from_str)
+            .collect::<Result<Vec<Keycode>, ParseInputNameError>>()?;
+
+        Ok(Binding::new(modifiers, trigger))
+    }
+}
+
+impl Display for AxisBinding {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} | {}", self.positive, self.negative)
+    }
+}
+
+impl FromStr for AxisBinding {
+    type Err = ParseInputNameError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (positive, negative) = s.split_once('|').ok_or_else(|| ParseInputNameError {
+            name: s.to_string(),
+        })?;
+
+        Ok(AxisBinding {
+            positive: positive.trim().parse()?,
+            negative: negative.trim().parse()?,
+        })
+    }
+}
+
+impl Display for BindingConflict {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "`{}` is used by {}", self.binding, self.used_by.join(", "))
+    }
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::Parse { line, message } => write!(f, "Line {line}: {message}"),
+            Error::UnknownAction(name) => write!(f, "Unknown action `{name}`"),
+            Error::UnknownAxis(name) => write!(f, "Unknown axis `{name}`"),
+            Error::BindingNotFound(binding) => {
+                write!(f, "The binding `{binding}` is not found")
+            }
+            Error::AxisBindingNotFound(binding) => {
+                write!(f, "The axis binding `{binding}` is not found")
+            }
+            Error::Conflict(conflict) => write!(f, "Binding conflict: {conflict}"),
+            Error::Io(e) => write!(f, "{e}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(value: io::Error) -> Self {
+        Error::Io(value)
+    }
+}
+
+fn normalize_modifiers(modifiers: &mut Vec<Keycode>) {
+    modifiers.sort_by_key(|m| *m as u32);
+    modifiers.dedup();
+}
+
+fn join_to_string<T: Display>(items: &[T]) -> String {
+    items
+        .iter()
+        .map(|i| i.to_string())
+        .collect::<Vec<String>>()
+        .join(", ")
+}
@@ -0,0 +1,235 @@
+use std::{
+    fs::{self, File},
+    io::{self, BufWriter, Write},
+    path::Path,
+    str::SplitWhitespace,
+    time::Duration,
+};
+
+use super::{
+    context::Error,
+    ffi::input::{
+        DivisionInput, DivisionKeyboardInput, DivisionMouseInput, DivisionTextInput,
+    },
+    input::ScrollDeltaUnit,
+};
+
+/// Per-frame input snapshots with frame deltas, captured by
+/// `CoreRunner::record_input` and fed back by `CoreRunner::replay_input`.
+///
+/// Stored as text, one frame per line:
+/// `delta_nanos pos_x pos_y buttons scroll_x scroll_y scroll_unit
+/// keys[4] repeats[4] text`, where text is a comma separated list of
+/// hex code points or `-` if empty
+#[derive(Clone, Default)]
+pub struct InputRecording {
+    frames: Vec<RecordedFrame>,
+}
+
+#[derive(Clone)]
+pub struct RecordedFrame {
+    pub delta: Duration,
+    pub mouse: DivisionMouseInput,
+    pub keyboard: DivisionKeyboardInput,
+    pub text: Vec<u32>,
+}
+
+/// Writes frames in the `InputRecording` format as they are recorded.
+/// Every frame is flushed, so the recording survives a crash of the app
+pub struct InputRecordingWriter<W: Write = BufWriter<File>> {
+    writer: W,
+}
+
+const HEADER: &str = "division-input-recording 1";
+const EMPTY_TEXT: &str = "-";
+
+impl InputRecording {
+    pub fn new() -> InputRecording {
+        InputRecording { frames: Vec::new() }
+    }
+
+    pub fn load_from_file(path: &Path) -> Result<InputRecording, Error> {
+        let recording = fs::read_to_string(path).map_err(|e| {
+            Error::Core(format!("Failed to read an input recording: {e}"))
+        })?;
+        Self::from_recording_str(&recording)
+    }
+
+    pub fn save_to_file(&self, path: &Path) -> Result<(), Error> {
+        fs::write(path, self.to_recording_string()).map_err(|e| {
+            Error::Core(format!("Failed to write an input recording: {e}"))
+        })
+    }
+
+    pub fn from_recording_str(recording: &str) -> Result<InputRecording, Error> {
+        let mut lines = recording.lines();
+        if lines.next() != Some(HEADER) {
+            return Err(Error::Core("Unknown input recording format".to_string()));
+        }
+
+        let frames = lines
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(index, line)| {
+                parse_frame(line).ok_or_else(|| {
+                    Error::Core(format!(
+                        "Malformed input recording frame at line {}",
+                        index + 2
+                    ))
+                })
+            })
+            .collect::<Result<Vec<RecordedFrame>, Error>>()?;
+
+        Ok(InputRecording { frames })
+    }
+
+    pub fn to_recording_string(&self) -> String {
+        let mut recording = format!("{HEADER}\n");
+        for frame in &self.frames {
+            recording.push_str(&format_frame(frame));
+            recording.push('\n');
+        }
+        recording
+    }
+
+    /// Appends the frame, copying the text input out of the core owned buffer
+    pub fn push_frame(&mut self, delta: Duration, input: &DivisionInput) {
+        self.frames.push(RecordedFrame::from_input(delta, input));
+    }
+
+    #[inline]
+    pub fn frames(&self) -> &[RecordedFrame] {
+        &self.frames
+    }
+}
+
+impl InputRecordingWriter {
+    pub fn create(path: &Path) -> Result<InputRecordingWriter, Error> {
+        File::create(path)
+            .and_then(|file| InputRecordingWriter::new(BufWriter::new(file)))
+            .map_err(|e| Error::Core(format!("Failed to create an input recording: {e}")))
+    }
+}
+
+impl<W: Write> InputRecordingWriter<W> {
+    /// Writes the header right away
+    pub fn new(mut writer: W) -> io::Result<InputRecordingWriter<W>> {
+        writeln!(writer, "{HEADER}")?;
+        writer.flush()?;
+        Ok(InputRecordingWriter { writer })
+    }
+
+    pub fn write_frame(
+        &mut self,
+        delta: Duration,
+        input: &DivisionInput,
+    ) -> io::Result<()> {
+        let frame = RecordedFrame::from_input(delta, input);
+        writeln!(self.writer, "{}", format_frame(&frame))?;
+        self.writer.flush()
+    }
+
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+impl RecordedFrame {
+    fn from_input(delta: Duration, input: &DivisionInput) -> RecordedFrame {
+        RecordedFrame {
+            delta,
+            mouse: input.mouse,
+            keyboard: input.keyboard,
+            text: input.text.characters().map(|c| c as u32).collect(),
+        }
+    }
+
+    /// The returned input borrows the text of the frame
+    pub fn to_input(&self) -> DivisionInput {
+        DivisionInput {
+            mouse: self.mouse,
+            keyboard: self.keyboard,
+            text: DivisionTextInput {
+                characters: self.text.as_ptr(),
+                character_count: self.text.len() as u32,
+            },
+        }
+    }
+}
+
+fn format_frame(frame: &RecordedFrame) -> String {
+    let mouse = &frame.mouse;
+    let keyboard = &frame.keyboard;
+    let text = match frame.text.is_empty() {
+        true => EMPTY_TEXT.to_string(),
+        false => frame
+            .text
+            .iter()
+            .map(|c| format!("{c:x}"))
+            .collect::<Vec<String>>()
+            .join(","),
+    };
+
+    format!(
+        "{} {} {} {} {} {} {} {} {} {}",
+        frame.delta.as_nanos(),
+        mouse.pos_x,
+        mouse.pos_y,
+        mouse.mouse_button_state_mask,
+        mouse.scroll_delta_x,
+        mouse.scroll_delta_y,
+        mouse.scroll_delta_unit,
+        join_mask(&keyboard.key_state_mask),
+        join_mask(&keyboard.key_repeat_mask),
+        text
+    )
+}
+
+fn parse_frame(line: &str) -> Option<RecordedFrame> {
+    let mut parts = line.split_whitespace();
+
+    let delta = Duration::from_nanos(parts.next()?.parse().ok()?);
+    let mouse = DivisionMouseInput {
+        pos_x: parts.next()?.parse().ok()?,
+        pos_y: parts.next()?.parse().ok()?,
+        mouse_button_state_mask: parts.next()?.parse().ok()?,
+        scroll_delta_x: parts.next()?.parse().ok()?,
+        scroll_delta_y: parts.next()?.parse().ok()?,
+        scroll_delta_unit: ScrollDeltaUnit::try_from(parts.next()?.parse::<u32>().ok()?)
+            .ok()?
+            .into(),
+    };
+    let keyboard = DivisionKeyboardInput {
+        key_state_mask: parse_mask(&mut parts)?,
+        key_repeat_mask: parse_mask(&mut parts)?,
+    };
+    let text = match parts.next()? {
+        EMPTY_TEXT => Vec::new(),
+        t => t
+            .split(',')
+            .map(|c| u32::from_str_radix(c, 16).ok())
+            .collect::<Option<Vec<u32>>>()?,
+    };
+
+    match parts.next() {
+        Some(_) => None,
+        None => Some(RecordedFrame {
+            delta,
+            mouse,
+            keyboard,
+            text,
+        }),
+    }
+}
+
+fn join_mask(mask: &[u32; 4]) -> String {
+    mask.map(|m| m.to_string()).join(" ")
+}
+
+fn parse_mask(parts: &mut SplitWhitespace) -> Option<[u32; 4]> {
+    let mut mask = [0; 4];
+    for m in &mut mask {
+        *m = parts.next()?.parse().ok()?;
+    }
+    Some(mask)
+}
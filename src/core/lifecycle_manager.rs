@@ -1,4 +1,4 @@
-use super::Context;
+use super::{Context, FixedTime, FrameTime, WindowEvent};
 
 pub trait LifecycleManagerBuilder {
     type LifecycleManager: LifecycleManager + 'static;
@@ -7,7 +7,19 @@ pub trait LifecycleManagerBuilder {
 }
 
 pub trait LifecycleManager: Sized {
-    fn draw(&mut self, context: &mut Context);
+    /// Called zero or more times before each `draw` when the runner has
+    /// a fixed timestep, once per every elapsed step
+    fn fixed_update(&mut self, _context: &mut Context, _fixed_time: &FixedTime) {}
+
+    /// Draws the frame, either with `Context::draw_render_passes` or by submitting
+    /// passes between `Context::begin_frame` and `Context::end_frame`
+    fn draw(&mut self, context: &mut Context, frame_time: &FrameTime);
     fn error(&mut self, context: &mut Context, error_code: i32, message: &str);
     fn cleanup(&mut self, context: &mut Context);
+
+    fn window_event(&mut self, _context: &mut Context, _event: &WindowEvent) {}
+    /// Return `false` to keep the window open
+    fn close_requested(&mut self, _context: &mut Context) -> bool {
+        true
+    }
 }
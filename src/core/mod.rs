@@ -1,26 +1,46 @@
+#[cfg(test)]
+mod tests;
+
 pub(crate) mod ffi;
 
 pub mod context;
 pub mod core_runner;
 pub mod font;
+pub mod frame_time;
+pub mod framebuffer;
 pub mod lifecycle_manager;
 pub mod image;
 pub mod input;
+pub mod input_map;
+pub mod input_recording;
 pub mod render_pass;
+pub mod render_target;
 pub mod shader;
+pub mod shader_diagnostics;
+pub mod shader_preprocessor;
+pub mod shader_reflection;
+pub mod streaming_instance_buffer;
 pub mod data_structures;
 pub mod texture;
 pub mod uniform_buffer;
 pub mod vertex_buffer;
+pub mod window;
 
 pub use context::{Context, DivisionId};
 pub use core_runner::*;
 pub use font::*;
+pub use frame_time::*;
 pub use lifecycle_manager::*;
 pub use image::*;
 pub use render_pass::*;
+pub use render_target::*;
 pub use data_structures::*;
 pub use shader::*;
+pub use shader_diagnostics::*;
+pub use shader_preprocessor::*;
+pub use shader_reflection::*;
+pub use streaming_instance_buffer::*;
 pub use texture::*;
 pub use uniform_buffer::*;
 pub use vertex_buffer::*;
+pub use window::*;
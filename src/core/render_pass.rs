@@ -19,7 +19,12 @@ use super::{
             DivisionRenderPassDescriptorCapabilityMask,
         },
         render_pass_instance::{
-            division_engine_render_pass_instance_draw, DivisionColor,
+            division_engine_render_pass_instance_draw,
+            division_engine_render_pass_instance_submit, DivisionColor,
+            DivisionLoadAction,
+        },
+        renderer::{
+            division_engine_renderer_begin_frame, division_engine_renderer_end_frame,
         },
     },
     Context, DivisionId,
@@ -31,22 +36,45 @@ pub use super::ffi::{
         DivisionAlphaBlendOperation as AlphaBlendOperation,
         DivisionAlphaBlendingOptions as AlphaBlendingOptions,
         DivisionColorMask as ColorMask,
+        DivisionCompareFunction as CompareFunction,
+        DivisionDepthOptions as DepthOptions,
         DivisionRenderPassDescriptor as RenderPassDescriptor,
         DivisionRenderPassDescriptorCapabilityMask as RenderPassDescriptorCapabilityMask,
+        DivisionStencilOperation as StencilOperation,
+        DivisionStencilOptions as StencilOptions,
     },
     render_pass_instance::{
-        DivisionIdWithBinding as IdWithBinding,
+        DivisionIdWithBinding as IdWithBinding, DivisionPixelRect as PixelRect,
         DivisionRenderPassInstance as RenderPassInstance,
         DivisionRenderPassInstanceCapabilityMask as RenderPassIsntanceCapabilityMask,
     },
 };
 
+/// What happens with the framebuffer contents before the submitted passes are drawn
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum LoadAction {
+    /// Fills the framebuffer with the color
+    Clear(Vector4),
+    /// Keeps what the previous submissions have drawn
+    Load,
+    /// The contents are undefined. Cheaper than `Load` when
+    /// the passes cover the whole framebuffer anyway
+    DontCare,
+}
+
 pub struct BorrowedRenderPass<'a> {
     render_pass: &'a mut RenderPassDescriptor,
     ctx: *mut DivisionContext,
     render_pass_id: u32,
 }
 
+/// Read only borrow of a render pass descriptor
+pub struct BorrowedRenderPassRef<'a> {
+    render_pass: &'a RenderPassDescriptor,
+    ctx: *mut DivisionContext,
+    render_pass_id: u32,
+}
+
 #[repr(transparent)]
 pub struct RenderPassInstanceOwned {
     pub instance: RenderPassInstance,
@@ -68,6 +96,21 @@ impl RenderPassDescriptor {
                 operation: AlphaBlendOperation::Add,
                 constant_blend_color: [0., 0., 0., 0.],
             },
+            depth_options: DepthOptions {
+                compare_function: CompareFunction::Less,
+                clear_value: 1.,
+                write_enabled: true,
+            },
+            stencil_options: StencilOptions {
+                compare_function: CompareFunction::Always,
+                reference: 0,
+                read_mask: u32::MAX,
+                write_mask: u32::MAX,
+                clear_value: 0,
+                stencil_fail_operation: StencilOperation::Keep,
+                depth_fail_operation: StencilOperation::Keep,
+                pass_operation: StencilOperation::Keep,
+            },
             capabilities_mask: RenderPassDescriptorCapabilityMask::None,
             color_mask: ColorMask::RGB,
             shader_program,
@@ -111,6 +154,67 @@ impl RenderPassDescriptor {
         self
     }
 
+    /// Discards fragments whose depth doesn't pass `compare_function` against
+    /// the depth buffer. Render targets need a depth attachment for it
+    pub fn depth_test(
+        mut self,
+        compare_function: CompareFunction,
+        write_enabled: bool,
+    ) -> Self {
+        self.depth_options.compare_function = compare_function;
+        self.depth_options.write_enabled = write_enabled;
+
+        self.capabilities_mask |= DivisionRenderPassDescriptorCapabilityMask::DepthTest;
+        self
+    }
+
+    /// Value the depth buffer is cleared with, `1` by default
+    pub fn depth_clear_value(mut self, clear_value: f32) -> Self {
+        self.depth_options.clear_value = clear_value;
+        self
+    }
+
+    /// Passes fragments for which `(reference & read_mask) compare_function
+    /// (stencil & read_mask)` holds. Needs a stencil buffer, that render targets
+    /// get with `RenderTargetDescriptor::with_stencil_attachment`
+    pub fn stencil_test(
+        mut self,
+        compare_function: CompareFunction,
+        reference: u32,
+        read_mask: u32,
+        write_mask: u32,
+    ) -> Self {
+        let stencil_options = &mut self.stencil_options;
+        stencil_options.compare_function = compare_function;
+        stencil_options.reference = reference;
+        stencil_options.read_mask = read_mask;
+        stencil_options.write_mask = write_mask;
+
+        self.capabilities_mask |= DivisionRenderPassDescriptorCapabilityMask::StencilTest;
+        self
+    }
+
+    /// Operations applied to the stencil buffer when the stencil test fails,
+    /// when it passes but the depth test fails and when both pass
+    pub fn stencil_operations(
+        mut self,
+        stencil_fail: StencilOperation,
+        depth_fail: StencilOperation,
+        pass: StencilOperation,
+    ) -> Self {
+        let stencil_options = &mut self.stencil_options;
+        stencil_options.stencil_fail_operation = stencil_fail;
+        stencil_options.depth_fail_operation = depth_fail;
+        stencil_options.pass_operation = pass;
+        self
+    }
+
+    /// Value the stencil buffer is cleared with, `0` by default
+    pub fn stencil_clear_value(mut self, clear_value: u32) -> Self {
+        self.stencil_options.clear_value = clear_value;
+        self
+    }
+
     pub fn color_mask(&mut self, color_mask: DivisionColorMask) -> &mut Self {
         self.color_mask = color_mask;
 
@@ -133,6 +237,8 @@ impl RenderPassInstance {
             uniform_fragment_buffer_count: 0,
             fragment_texture_count: 0,
             render_pass_descriptor_id: descriptor_id,
+            scissor_rect: PixelRect::new(0, 0, 0, 0),
+            viewport_rect: PixelRect::new(0, 0, 0, 0),
             capabilities_mask: RenderPassIsntanceCapabilityMask::None,
         }
     }
@@ -163,6 +269,23 @@ impl RenderPassInstance {
         self
     }
 
+    /// Discards the fragments outside of the rect
+    pub fn scissor(mut self, scissor_rect: PixelRect) -> Self {
+        self.scissor_rect = scissor_rect;
+        self.capabilities_mask |= RenderPassIsntanceCapabilityMask::Scissor;
+
+        self
+    }
+
+    /// Maps the normalized device coordinates to the rect
+    /// instead of the whole framebuffer
+    pub fn viewport(mut self, viewport_rect: PixelRect) -> Self {
+        self.viewport_rect = viewport_rect;
+        self.capabilities_mask |= RenderPassIsntanceCapabilityMask::Viewport;
+
+        self
+    }
+
     pub unsafe fn set_uniform_vertex_buffer_from_ref<'a>(
         &'a mut self,
         buffer: &'a IdWithBinding,
@@ -303,6 +426,13 @@ impl Context {
         &mut self,
         descriptor: &RenderPassDescriptor,
     ) -> Result<DivisionId, Error> {
+        let (per_vertex_attributes, per_instance_attributes) =
+            self.vertex_buffer_attributes(descriptor.vertex_buffer_id)?;
+        let reflection = self.shader_program_reflection(descriptor.shader_program)?;
+        reflection
+            .validate_vertex_attributes(&per_vertex_attributes, &per_instance_attributes)
+            .map_err(Error::ShaderValidation)?;
+
         let mut pass_id = 0;
         unsafe {
             if !division_engine_render_pass_descriptor_alloc(
@@ -314,6 +444,10 @@ impl Context {
             }
         }
 
+        self.runner_state_mut()
+            .render_pass_reflections
+            .insert(pass_id, reflection);
+
         Ok(pass_id)
     }
 
@@ -333,11 +467,69 @@ impl Context {
         }
     }
 
+    pub fn borrow_render_pass_descriptor(
+        &mut self,
+        render_pass_id: DivisionId,
+    ) -> BorrowedRenderPassRef<'_> {
+        unsafe {
+            BorrowedRenderPassRef {
+                ctx: &mut *self,
+                render_pass_id,
+                render_pass: &*division_engine_render_pass_descriptor_borrow(
+                    &mut *self,
+                    render_pass_id,
+                ),
+            }
+        }
+    }
+
+    /// Starts a frame. Render passes are submitted between `begin_frame`
+    /// and `end_frame`, which presents the frame
+    pub fn begin_frame(&mut self) {
+        unsafe {
+            division_engine_renderer_begin_frame(self);
+        }
+    }
+
+    pub fn end_frame(&mut self) {
+        unsafe {
+            division_engine_renderer_end_frame(self);
+        }
+    }
+
+    /// Draws the passes into the current frame. Submissions are drawn in order,
+    /// so systems can contribute to the same frame by submitting with `LoadAction::Load`.
+    /// Nothing is drawn if an instance sets bindings its shader doesn't declare
+    pub fn submit_render_passes(
+        &mut self,
+        load_action: LoadAction,
+        instances: &[RenderPassInstance],
+    ) -> Result<(), Error> {
+        self.validate_render_pass_instances(instances)?;
+
+        let (load_action, clear_color) = load_action.to_division_load_action();
+        unsafe {
+            division_engine_render_pass_instance_submit(
+                self,
+                load_action,
+                &clear_color,
+                instances.as_ptr(),
+                instances.len() as u32,
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Draws a whole frame, cleared with the color, out of the passes.
+    /// Must not be called between `begin_frame` and `end_frame`
     pub fn draw_render_passes(
         &mut self,
         clear_color: Vector4,
         instances: &[RenderPassInstance],
-    ) {
+    ) -> Result<(), Error> {
+        self.validate_render_pass_instances(instances)?;
+
         unsafe {
             division_engine_render_pass_instance_draw(
                 self,
@@ -346,16 +538,58 @@ impl Context {
                 instances.len() as u32,
             );
         }
+
+        Ok(())
     }
 
     #[inline(always)]
     pub fn delete_render_pass_descriptor(&mut self, render_pass_id: DivisionId) {
+        self.runner_state_mut()
+            .render_pass_reflections
+            .remove(&render_pass_id);
+
         unsafe {
             division_engine_render_pass_descriptor_free(&mut *self, render_pass_id);
         }
     }
 }
 
+impl LoadAction {
+    pub(crate) fn to_division_load_action(self) -> (DivisionLoadAction, DivisionColor) {
+        let no_color = DivisionColor {
+            r: 0.,
+            g: 0.,
+            b: 0.,
+            a: 0.,
+        };
+
+        match self {
+            LoadAction::Clear(c) => (
+                DivisionLoadAction::Clear,
+                DivisionColor {
+                    r: c.r(),
+                    g: c.g(),
+                    b: c.b(),
+                    a: c.a(),
+                },
+            ),
+            LoadAction::Load => (DivisionLoadAction::Load, no_color),
+            LoadAction::DontCare => (DivisionLoadAction::DontCare, no_color),
+        }
+    }
+}
+
+impl PixelRect {
+    pub fn new(x: i32, y: i32, width: u32, height: u32) -> PixelRect {
+        PixelRect {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+}
+
 impl IdWithBinding {
     pub fn new(id: u32, shader_binding: u32) -> IdWithBinding {
         IdWithBinding { id, shader_binding }
@@ -370,6 +604,25 @@ fn has_constant_color(blend: AlphaBlend) -> bool {
 }
 
 impl<'a> Drop for BorrowedRenderPass<'a> {
+    fn drop(&mut self) {
+        unsafe {
+            // The shader may have been changed, its reflection is fetched again
+            // when the pass is drawn next time
+            (*self.ctx)
+                .runner_state_mut()
+                .render_pass_reflections
+                .remove(&self.render_pass_id);
+
+            division_engine_render_pass_descriptor_return(
+                self.ctx,
+                self.render_pass_id,
+                self.render_pass,
+            );
+        }
+    }
+}
+
+impl<'a> Drop for BorrowedRenderPassRef<'a> {
     fn drop(&mut self) {
         unsafe {
             division_engine_render_pass_descriptor_return(
@@ -381,6 +634,14 @@ impl<'a> Drop for BorrowedRenderPass<'a> {
     }
 }
 
+impl<'a> Deref for BorrowedRenderPassRef<'a> {
+    type Target = RenderPassDescriptor;
+
+    fn deref(&self) -> &Self::Target {
+        self.render_pass
+    }
+}
+
 impl<'a> Deref for BorrowedRenderPass<'a> {
     type Target = RenderPassDescriptor;
 
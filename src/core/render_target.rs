@@ -0,0 +1,183 @@
+use division_math::Vector4;
+
+use super::{
+    context::Error,
+    ffi::{
+        render_pass_instance::DivisionColor,
+        render_target::{
+            division_engine_render_pass_instance_draw_to_target,
+            division_engine_render_pass_instance_submit_to_target,
+            division_engine_render_target_alloc,
+            division_engine_render_target_color_texture,
+            division_engine_render_target_depth_texture,
+            division_engine_render_target_free,
+        },
+    },
+    texture::TextureLayout,
+    Context, DivisionId, LoadAction, RenderPassInstance, TextureFormat,
+};
+
+pub use super::ffi::render_target::{
+    DivisionRenderTargetDescriptor as RenderTargetDescriptor,
+};
+
+impl RenderTargetDescriptor {
+    pub fn new(width: usize, height: usize) -> RenderTargetDescriptor {
+        RenderTargetDescriptor {
+            width: width as u32,
+            height: height as u32,
+            color_format: TextureFormat::RGBA32Uint,
+            has_depth_attachment: false,
+            has_stencil_attachment: false,
+        }
+    }
+
+    pub fn with_color_format(mut self, color_format: TextureFormat) -> Self {
+        self.color_format = color_format;
+        self
+    }
+
+    pub fn with_depth_attachment(mut self) -> Self {
+        self.has_depth_attachment = true;
+        self
+    }
+
+    /// Adds a stencil buffer for passes with `RenderPassDescriptor::stencil_test`.
+    /// With a depth attachment both share one `Depth24Stencil8` texture,
+    /// returned by `render_target_depth_texture`
+    pub fn with_stencil_attachment(mut self) -> Self {
+        self.has_stencil_attachment = true;
+        self
+    }
+}
+
+impl Context {
+    /// Creates an offscreen framebuffer. Its attachments are textures owned by
+    /// the render target, so they can be sampled by later render passes, but must
+    /// not be deleted with `delete_texture_buffer`
+    pub fn create_render_target(
+        &mut self,
+        descriptor: &RenderTargetDescriptor,
+    ) -> Result<DivisionId, Error> {
+        if descriptor.width == 0 || descriptor.height == 0 {
+            return Err(Error::Core(format!(
+                "Render target size must not be zero, got {}x{}",
+                descriptor.width, descriptor.height
+            )));
+        }
+        if descriptor.color_format.is_depth() {
+            return Err(Error::Core(format!(
+                "{:?} can't be the color attachment format of a render target",
+                descriptor.color_format
+            )));
+        }
+
+        let mut render_target_id = 0;
+        unsafe {
+            if !division_engine_render_target_alloc(
+                self,
+                descriptor,
+                &mut render_target_id,
+            ) {
+                return Err(Error::Core("Failed to create a render target".to_string()));
+            }
+        }
+
+        let color_texture_id = self.render_target_color_texture(render_target_id);
+        self.runner_state_mut().texture_layouts.insert(
+            color_texture_id,
+            TextureLayout {
+                texture_format: descriptor.color_format,
+                width: descriptor.width,
+                height: descriptor.height,
+                mip_level_count: 1,
+            },
+        );
+
+        Ok(render_target_id)
+    }
+
+    /// Texture of the color attachment. Like the window framebuffer,
+    /// its origin is the bottom left corner
+    pub fn render_target_color_texture(
+        &mut self,
+        render_target_id: DivisionId,
+    ) -> DivisionId {
+        unsafe { division_engine_render_target_color_texture(self, render_target_id) }
+    }
+
+    pub fn render_target_depth_texture(
+        &mut self,
+        render_target_id: DivisionId,
+    ) -> Option<DivisionId> {
+        let mut texture_id = 0;
+        unsafe {
+            division_engine_render_target_depth_texture(
+                self,
+                render_target_id,
+                &mut texture_id,
+            )
+        }
+        .then_some(texture_id)
+    }
+
+    /// Same as `draw_render_passes`, but draws into the render target
+    /// instead of the window
+    pub fn draw_render_passes_to_target(
+        &mut self,
+        render_target_id: DivisionId,
+        clear_color: Vector4,
+        instances: &[RenderPassInstance],
+    ) -> Result<(), Error> {
+        self.validate_render_pass_instances(instances)?;
+
+        unsafe {
+            division_engine_render_pass_instance_draw_to_target(
+                self,
+                render_target_id,
+                &clear_color as *const Vector4 as *const DivisionColor,
+                instances.as_ptr(),
+                instances.len() as u32,
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Same as `submit_render_passes`, but draws into the render target
+    /// instead of the window
+    pub fn submit_render_passes_to_target(
+        &mut self,
+        render_target_id: DivisionId,
+        load_action: LoadAction,
+        instances: &[RenderPassInstance],
+    ) -> Result<(), Error> {
+        self.validate_render_pass_instances(instances)?;
+
+        let (load_action, clear_color) = load_action.to_division_load_action();
+        unsafe {
+            division_engine_render_pass_instance_submit_to_target(
+                self,
+                render_target_id,
+                load_action,
+                &clear_color,
+                instances.as_ptr(),
+                instances.len() as u32,
+            );
+        }
+
+        Ok(())
+    }
+
+    #[inline(always)]
+    pub fn delete_render_target(&mut self, render_target_id: DivisionId) {
+        let color_texture_id = self.render_target_color_texture(render_target_id);
+        self.runner_state_mut()
+            .texture_layouts
+            .remove(&color_texture_id);
+
+        unsafe {
+            division_engine_render_target_free(self, render_target_id);
+        }
+    }
+}
@@ -1,11 +1,17 @@
-use std::ffi::CString;
+use std::{
+    collections::HashMap,
+    ffi::{CStr, CString},
+    hash::Hash,
+    path::{Path, PathBuf},
+};
 
 use super::{
     ffi::shader::{
         division_engine_shader_program_alloc, division_engine_shader_program_free,
-        DivisionShaderSourceDescriptor,
+        division_engine_shader_program_last_error, DivisionShaderSourceDescriptor,
     },
-    Context, DivisionId, context::Error,
+    Context, DivisionId, PreprocessedShader, ShaderCompileError, ShaderPreprocessor,
+    ShaderProgram, context::Error,
 };
 
 pub use super::ffi::shader::DivisionShaderType as ShaderType;
@@ -15,13 +21,19 @@ pub struct ShaderSourceDescriptor {
     shader_type: ShaderType,
     entry_point: CString,
     source: CString,
+    preprocessed: Option<PreprocessedShader>,
+}
+
+/// Programs built from the same sources with different defines
+pub struct ShaderVariants<K> {
+    programs: HashMap<K, ShaderProgram>,
 }
 
 impl Context {
     pub fn create_shader_program(
         &mut self,
         descriptors: &[ShaderSourceDescriptor],
-    ) -> Result<DivisionId, Error> {
+    ) -> Result<ShaderProgram, Error> {
         let c_desc: Vec<DivisionShaderSourceDescriptor> = descriptors
             .into_iter()
             .map(|d| DivisionShaderSourceDescriptor {
@@ -40,11 +52,82 @@ impl Context {
                 c_desc.len() as i32,
                 &mut shader_id,
             ) {
-                return Err(Error::Core(String::from("Failed to create a shader")));
+                return Err(self.shader_program_error(descriptors));
+            }
+        }
+
+        match self.shader_program_reflection(shader_id) {
+            Ok(reflection) => Ok(ShaderProgram {
+                id: shader_id,
+                reflection,
+            }),
+            Err(e) => {
+                self.delete_shader_program(shader_id);
+                Err(e)
+            }
+        }
+    }
+
+    /// Creates a program from GLSL sources for the active backend.
+    ///
+    /// OpenGL compiles them as is. Metal gets them cross-compiled to MSL,
+    /// with `entry_point` as the name of the generated function
+    pub fn create_shader_program_from_glsl(
+        &mut self,
+        descriptors: &[ShaderSourceDescriptor],
+    ) -> Result<ShaderProgram, Error> {
+        #[cfg(target_os = "macos")]
+        {
+            let msl_descriptors = descriptors
+                .iter()
+                .map(cross_compile_to_msl)
+                .collect::<Result<Vec<_>, _>>()?;
+            self.create_shader_program(&msl_descriptors)
+        }
+
+        #[cfg(not(target_os = "macos"))]
+        self.create_shader_program(descriptors)
+    }
+
+    /// Creates a program for every variant, with the same sources preprocessed
+    /// with the variant's preprocessor
+    pub fn create_shader_variants<K: Eq + Hash>(
+        &mut self,
+        files: &[(ShaderType, &str, &Path)],
+        variants: impl IntoIterator<Item = (K, ShaderPreprocessor)>,
+    ) -> Result<ShaderVariants<K>, Error> {
+        let mut shader_variants = ShaderVariants {
+            programs: HashMap::new(),
+        };
+
+        for (key, preprocessor) in variants {
+            let program = files
+                .iter()
+                .map(|&(shader_type, entry_point, path)| {
+                    ShaderSourceDescriptor::from_file(
+                        shader_type,
+                        entry_point,
+                        path,
+                        &preprocessor,
+                    )
+                })
+                .collect::<Result<Vec<_>, _>>()
+                .and_then(|descriptors| self.create_shader_program(&descriptors));
+
+            match program {
+                Ok(program) => {
+                    if let Some(old) = shader_variants.programs.insert(key, program) {
+                        self.delete_shader_program(old.id);
+                    }
+                }
+                Err(e) => {
+                    shader_variants.delete(self);
+                    return Err(e);
+                }
             }
         }
 
-        Ok(shader_id)
+        Ok(shader_variants)
     }
 
     pub fn delete_shader_program(&mut self, id: DivisionId) {
@@ -52,6 +135,31 @@ impl Context {
             division_engine_shader_program_free(&mut *self, id);
         }
     }
+
+    fn shader_program_error(&mut self, descriptors: &[ShaderSourceDescriptor]) -> Error {
+        let mut shader_type = 0;
+        let log = unsafe {
+            let log = division_engine_shader_program_last_error(self, &mut shader_type);
+            match log.is_null() {
+                true => return Error::Core(String::from("Failed to create a shader")),
+                false => CStr::from_ptr(log).to_string_lossy().into_owned(),
+            }
+        };
+        let Ok(shader_type) = ShaderType::try_from(shader_type) else {
+            return Error::Core(log);
+        };
+
+        let preprocessed = descriptors
+            .iter()
+            .find(|d| d.shader_type == shader_type)
+            .and_then(|d| d.preprocessed.as_ref());
+
+        Error::ShaderCompilation(ShaderCompileError::from_glsl_log(
+            shader_type,
+            &log,
+            preprocessed,
+        ))
+    }
 }
 
 impl ShaderSourceDescriptor {
@@ -60,6 +168,94 @@ impl ShaderSourceDescriptor {
             source: CString::new(source).unwrap(),
             entry_point: CString::new(entry_point).unwrap(),
             shader_type,
+            preprocessed: None,
         };
     }
+
+    /// Reads and preprocesses the source. Compile errors of the program
+    /// point to the original files and lines
+    pub fn from_file(
+        shader_type: ShaderType,
+        entry_point: &str,
+        path: &Path,
+        preprocessor: &ShaderPreprocessor,
+    ) -> Result<Self, Error> {
+        let preprocessed = preprocessor
+            .process_file(path)
+            .map_err(|e| Error::Core(e.to_string()))?;
+
+        Ok(Self::from_preprocessed(shader_type, entry_point, preprocessed))
+    }
+
+    pub fn from_preprocessed(
+        shader_type: ShaderType,
+        entry_point: &str,
+        preprocessed: PreprocessedShader,
+    ) -> Self {
+        ShaderSourceDescriptor {
+            source: CString::new(preprocessed.source.as_str()).unwrap(),
+            entry_point: CString::new(entry_point).unwrap(),
+            shader_type,
+            preprocessed: Some(preprocessed),
+        }
+    }
+
+    /// Files the source was read from, empty if it was passed as a string
+    pub fn source_files(&self) -> &[PathBuf] {
+        self.preprocessed.as_ref().map_or(&[], |p| p.files())
+    }
+}
+
+impl<K: Eq + Hash> ShaderVariants<K> {
+    pub fn get(&self, key: &K) -> Option<&ShaderProgram> {
+        self.programs.get(key)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&K, &ShaderProgram)> {
+        self.programs.iter()
+    }
+
+    pub fn delete(&mut self, context: &mut Context) {
+        for (_, program) in self.programs.drain() {
+            context.delete_shader_program(program.id);
+        }
+    }
+}
+
+#[cfg(target_os = "macos")]
+fn cross_compile_to_msl(
+    descriptor: &ShaderSourceDescriptor,
+) -> Result<ShaderSourceDescriptor, Error> {
+    use division_shader_compiler_rust::{
+        ShaderCompiler, ShaderType as CompilerShaderType,
+    };
+
+    use super::{DiagnosticSeverity, ShaderDiagnostic};
+
+    let compiler_shader_type = match descriptor.shader_type {
+        ShaderType::Vertex => CompilerShaderType::Vertex,
+        ShaderType::Fragment => CompilerShaderType::Fragment,
+    };
+    let entry_point = descriptor.entry_point.to_string_lossy();
+
+    ShaderCompiler::new()
+        .compile_glsl_to_metal(
+            &descriptor.source.to_string_lossy(),
+            &entry_point,
+            compiler_shader_type,
+        )
+        .map(|msl| {
+            ShaderSourceDescriptor::new(descriptor.shader_type, &entry_point, &msl)
+        })
+        .map_err(|_| {
+            Error::ShaderCompilation(ShaderCompileError {
+                shader_type: descriptor.shader_type,
+                diagnostics: vec![ShaderDiagnostic {
+                    severity: DiagnosticSeverity::Error,
+                    file: descriptor.source_files().first().cloned(),
+                    line: None,
+                    message: String::from("Failed to cross-compile GLSL to MSL"),
+                }],
+            })
+        })
 }
@@ -0,0 +1,155 @@
+use std::{fmt::Display, path::PathBuf};
+
+use super::{PreprocessedShader, ShaderType};
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ShaderCompileError {
+    pub shader_type: ShaderType,
+    pub diagnostics: Vec<ShaderDiagnostic>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ShaderDiagnostic {
+    pub severity: DiagnosticSeverity,
+    /// Original file of the line if the source was preprocessed
+    pub file: Option<PathBuf>,
+    pub line: Option<u32>,
+    pub message: String,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum DiagnosticSeverity {
+    Error,
+    Warning,
+}
+
+impl ShaderCompileError {
+    /// Parses a GLSL compiler log in the glslang, Mesa or NVIDIA formats.
+    /// Lines are mapped to the original files when the source was preprocessed
+    pub fn from_glsl_log(
+        shader_type: ShaderType,
+        log: &str,
+        preprocessed: Option<&PreprocessedShader>,
+    ) -> ShaderCompileError {
+        ShaderCompileError {
+            shader_type,
+            diagnostics: log
+                .lines()
+                .filter_map(|l| parse_log_line(l, preprocessed))
+                .collect(),
+        }
+    }
+
+    pub fn errors(&self) -> impl Iterator<Item = &ShaderDiagnostic> {
+        self.diagnostics
+            .iter()
+            .filter(|d| d.severity == DiagnosticSeverity::Error)
+    }
+}
+
+impl Display for ShaderCompileError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Failed to compile the {:?} shader", self.shader_type)?;
+        for diagnostic in &self.diagnostics {
+            write!(f, "\n{diagnostic}")?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Display for ShaderDiagnostic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match (&self.file, self.line) {
+            (Some(file), Some(line)) => write!(f, "{}:{line}: ", file.display())?,
+            (None, Some(line)) => write!(f, "{line}: ")?,
+            _ => {}
+        }
+
+        let severity = match self.severity {
+            DiagnosticSeverity::Error => "error",
+            DiagnosticSeverity::Warning => "warning",
+        };
+        write!(f, "{severity}: {}", self.message)
+    }
+}
+
+fn parse_log_line(
+    line: &str,
+    preprocessed: Option<&PreprocessedShader>,
+) -> Option<ShaderDiagnostic> {
+    let mut rest = line.trim();
+    if rest.is_empty() || rest.contains("compilation errors") {
+        return None;
+    }
+
+    let mut severity = None;
+    for (prefix, s) in [
+        ("ERROR:", DiagnosticSeverity::Error),
+        ("WARNING:", DiagnosticSeverity::Warning),
+    ] {
+        if let Some(r) = rest.strip_prefix(prefix) {
+            severity = Some(s);
+            rest = r.trim_start();
+        }
+    }
+
+    let line_number = match parse_location(rest) {
+        Some((line_number, r)) => {
+            rest = r.trim_start().trim_start_matches(':').trim_start();
+            Some(line_number)
+        }
+        None => None,
+    };
+
+    for (prefix, s) in [
+        ("error", DiagnosticSeverity::Error),
+        ("warning", DiagnosticSeverity::Warning),
+    ] {
+        if let Some(r) = rest.strip_prefix(prefix) {
+            // NVIDIA adds a code, `error C0000: message`
+            if let Some((_, message)) = r.split_once(':') {
+                severity = severity.or(Some(s));
+                rest = message.trim_start();
+            }
+            break;
+        }
+    }
+
+    let location = line_number.and_then(|l| preprocessed?.source_location(l));
+
+    Some(ShaderDiagnostic {
+        severity: severity.unwrap_or(DiagnosticSeverity::Error),
+        file: location.map(|l| l.file.to_path_buf()),
+        line: location.map(|l| l.line).or(line_number),
+        message: rest.to_string(),
+    })
+}
+
+/// Parses `0:12`, `0:12(5)` or `0(12)` and returns the line with the rest
+fn parse_location(text: &str) -> Option<(u32, &str)> {
+    let source_end = text.find(|c: char| !c.is_ascii_digit())?;
+    if source_end == 0 {
+        return None;
+    }
+
+    let rest = &text[source_end..];
+    let (is_parenthesized, rest) = match rest.chars().next()? {
+        ':' => (false, &rest[1..]),
+        '(' => (true, &rest[1..]),
+        _ => return None,
+    };
+
+    let line_end = rest.find(|c: char| !c.is_ascii_digit()).unwrap_or(rest.len());
+    let line = rest[..line_end].parse().ok()?;
+    let mut rest = &rest[line_end..];
+
+    if is_parenthesized {
+        rest = rest.strip_prefix(')')?;
+    } else if rest.starts_with('(') {
+        let column_end = rest.find(')')?;
+        rest = &rest[column_end + 1..];
+    }
+
+    Some((line, rest))
+}
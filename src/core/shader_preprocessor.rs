@@ -0,0 +1,253 @@
+use std::{
+    fmt::Display,
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+/// Expands `#include "file"` relative to the including file, skips files
+/// that were already included if they have `#pragma once`, and injects
+/// `#define`s right after the `#version` directive.
+///
+/// System includes like `#include <metal_stdlib>` and conditional directives
+/// are left to the shader compiler
+#[derive(Clone, Debug, Default)]
+pub struct ShaderPreprocessor {
+    defines: Vec<(String, String)>,
+}
+
+/// Preprocessed source with the original file and line of every line
+#[derive(Clone, Debug)]
+pub struct PreprocessedShader {
+    pub source: String,
+    files: Vec<PathBuf>,
+    line_map: Vec<(usize, u32)>,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct SourceLocation<'a> {
+    pub file: &'a Path,
+    pub line: u32,
+}
+
+#[derive(Debug)]
+pub enum PreprocessError {
+    Io { path: PathBuf, error: io::Error },
+    IncludeCycle { path: PathBuf },
+    InvalidDirective { path: PathBuf, line: u32, message: String },
+}
+
+impl ShaderPreprocessor {
+    pub fn new() -> ShaderPreprocessor {
+        ShaderPreprocessor::default()
+    }
+
+    pub fn define(mut self, name: &str, value: &str) -> Self {
+        self.defines.retain(|(n, _)| n != name);
+        self.defines.push((name.to_string(), value.to_string()));
+        self
+    }
+
+    pub fn defines(&self) -> &[(String, String)] {
+        &self.defines
+    }
+
+    pub fn process_file(
+        &self,
+        path: &Path,
+    ) -> Result<PreprocessedShader, PreprocessError> {
+        let source = read_source(path)?;
+        self.process_source(path, &source)
+    }
+
+    /// `path` is used to resolve includes and to map the lines of the source
+    pub fn process_source(
+        &self,
+        path: &Path,
+        source: &str,
+    ) -> Result<PreprocessedShader, PreprocessError> {
+        let mut output = PreprocessedShader {
+            source: String::new(),
+            files: Vec::new(),
+            line_map: Vec::new(),
+        };
+        let mut state = IncludeState {
+            stack: Vec::new(),
+            once_files: Vec::new(),
+        };
+
+        let has_version = source
+            .lines()
+            .any(|l| l.trim_start().starts_with("#version"));
+        if !has_version {
+            let file_index = output.add_file(path);
+            self.write_defines(&mut output, file_index, 0);
+        }
+
+        self.process(&mut output, &mut state, path, source, true)?;
+
+        Ok(output)
+    }
+
+    fn process(
+        &self,
+        output: &mut PreprocessedShader,
+        state: &mut IncludeState,
+        path: &Path,
+        source: &str,
+        is_root: bool,
+    ) -> Result<(), PreprocessError> {
+        let canonical_path =
+            fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+        if state.stack.contains(&canonical_path) {
+            return Err(PreprocessError::IncludeCycle {
+                path: path.to_path_buf(),
+            });
+        }
+        if state.once_files.contains(&canonical_path) {
+            return Ok(());
+        }
+        state.stack.push(canonical_path.clone());
+
+        let file_index = output.add_file(path);
+
+        for (index, line) in source.lines().enumerate() {
+            let line_number = index as u32 + 1;
+            let directive = line.trim_start();
+
+            if let Some(include) = directive
+                .strip_prefix("#include")
+                .filter(|i| !i.trim_start().starts_with('<'))
+            {
+                let include_path = parse_include_path(include).ok_or_else(|| {
+                    PreprocessError::InvalidDirective {
+                        path: path.to_path_buf(),
+                        line: line_number,
+                        message: format!("Expected `#include \"file\"`, found `{line}`"),
+                    }
+                })?;
+                let include_path = path
+                    .parent()
+                    .unwrap_or_else(|| Path::new(""))
+                    .join(include_path);
+
+                let include_source = read_source(&include_path)?;
+                self.process(output, state, &include_path, &include_source, false)?;
+                continue;
+            }
+
+            if is_pragma_once(directive) {
+                state.once_files.push(canonical_path.clone());
+                continue;
+            }
+
+            output.push_line(line, file_index, line_number);
+
+            if is_root && directive.starts_with("#version") {
+                self.write_defines(output, file_index, line_number);
+            }
+        }
+
+        state.stack.pop();
+        Ok(())
+    }
+
+    fn write_defines(
+        &self,
+        output: &mut PreprocessedShader,
+        file_index: usize,
+        line: u32,
+    ) {
+        for (name, value) in &self.defines {
+            output.push_line(&format!("#define {name} {value}"), file_index, line);
+        }
+    }
+}
+
+impl PreprocessedShader {
+    /// Original location of the 1-based line of the preprocessed source.
+    /// Injected defines are mapped to the `#version` line
+    pub fn source_location(&self, line: u32) -> Option<SourceLocation<'_>> {
+        let &(file_index, line) = self.line_map.get((line as usize).checked_sub(1)?)?;
+        Some(SourceLocation {
+            file: &self.files[file_index],
+            line,
+        })
+    }
+
+    /// The processed file and every file it includes
+    pub fn files(&self) -> &[PathBuf] {
+        &self.files
+    }
+
+    fn add_file(&mut self, path: &Path) -> usize {
+        match self.files.iter().position(|f| f == path) {
+            Some(index) => index,
+            None => {
+                self.files.push(path.to_path_buf());
+                self.files.len() - 1
+            }
+        }
+    }
+
+    fn push_line(&mut self, line: &str, file_index: usize, line_number: u32) {
+        self.source.push_str(line);
+        self.source.push('\n');
+        self.line_map.push((file_index, line_number));
+    }
+}
+
+impl<'a> Display for SourceLocation<'a> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}", self.file.display(), self.line)
+    }
+}
+
+impl Display for PreprocessError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PreprocessError::Io { path, error } => {
+                write!(f, "Failed to read `{}`: {error}", path.display())
+            }
+            PreprocessError::IncludeCycle { path } => {
+                write!(f, "`{}` includes itself", path.display())
+            }
+            PreprocessError::InvalidDirective {
+                path,
+                line,
+                message,
+            } => write!(f, "{}:{line}: {message}", path.display()),
+        }
+    }
+}
+
+struct IncludeState {
+    stack: Vec<PathBuf>,
+    once_files: Vec<PathBuf>,
+}
+
+fn read_source(path: &Path) -> Result<String, PreprocessError> {
+    fs::read_to_string(path).map_err(|error| PreprocessError::Io {
+        path: path.to_path_buf(),
+        error,
+    })
+}
+
+fn parse_include_path(directive_rest: &str) -> Option<&str> {
+    let rest = directive_rest.trim().strip_prefix('"')?;
+    let end = rest.find('"')?;
+    let after = rest[end + 1..].trim_start();
+    if !after.is_empty() && !after.starts_with("//") {
+        return None;
+    }
+
+    Some(&rest[..end])
+}
+
+fn is_pragma_once(directive: &str) -> bool {
+    let mut words = directive
+        .strip_prefix('#')
+        .map(|d| d.split_whitespace())
+        .into_iter()
+        .flatten();
+    words.next() == Some("pragma") && words.next() == Some("once")
+}
@@ -0,0 +1,369 @@
+use std::{
+    ffi::{c_char, CStr},
+    fmt::Display,
+    mem::MaybeUninit,
+};
+
+use super::{
+    context::Error,
+    ffi::shader::{
+        division_engine_shader_program_get_reflection, DivisionShaderReflection,
+    },
+    Context, DivisionId, IdWithBinding, RenderPassInstance, ShaderType,
+    ShaderVariableType, VertexAttributeDescriptor,
+};
+
+/// Shader program id with what its sources declare
+#[derive(Clone, Debug)]
+pub struct ShaderProgram {
+    pub id: DivisionId,
+    pub reflection: ShaderReflection,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ShaderReflection {
+    pub vertex_inputs: Vec<ShaderVertexInput>,
+    pub uniform_blocks: Vec<ShaderUniformBlock>,
+    pub textures: Vec<ShaderTexture>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ShaderVertexInput {
+    pub name: String,
+    pub variable_type: ShaderVariableType,
+    pub location: i32,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ShaderUniformBlock {
+    pub name: String,
+    pub shader_type: ShaderType,
+    pub binding: u32,
+    pub size_bytes: usize,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ShaderTexture {
+    pub name: String,
+    pub shader_type: ShaderType,
+    pub binding: u32,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum ShaderValidationError {
+    MissingVertexAttribute {
+        name: String,
+        location: i32,
+        shader_type: ShaderVariableType,
+    },
+    VertexAttributeTypeMismatch {
+        name: String,
+        location: i32,
+        shader_type: ShaderVariableType,
+        buffer_type: ShaderVariableType,
+    },
+    DuplicateVertexAttribute {
+        location: i32,
+    },
+    UnknownUniformBinding {
+        shader_type: ShaderType,
+        binding: u32,
+    },
+    UnknownTextureBinding {
+        binding: u32,
+    },
+}
+
+impl Context {
+    pub fn shader_program_reflection(
+        &mut self,
+        shader_program_id: DivisionId,
+    ) -> Result<ShaderReflection, Error> {
+        unsafe {
+            let mut reflection = MaybeUninit::uninit();
+            if !division_engine_shader_program_get_reflection(
+                self,
+                shader_program_id,
+                reflection.as_mut_ptr(),
+            ) {
+                return Err(Error::Core(format!(
+                    "Failed to get reflection of the shader program `{shader_program_id}`"
+                )));
+            }
+
+            ShaderReflection::from_division(&reflection.assume_init())
+        }
+    }
+
+    /// Checks that the shader of the instance's render pass declares
+    /// every uniform buffer and texture binding the instance sets
+    pub fn validate_render_pass_instance(
+        &mut self,
+        instance: &RenderPassInstance,
+    ) -> Result<(), Error> {
+        self.validate_render_pass_instances(std::slice::from_ref(instance))
+    }
+
+    /// Validates the instances against the shader reflection cached per render pass
+    /// descriptor. It's fetched only for descriptors that aren't cached yet,
+    /// e.g. after they were borrowed mutably
+    pub(crate) fn validate_render_pass_instances(
+        &mut self,
+        instances: &[RenderPassInstance],
+    ) -> Result<(), Error> {
+        for instance in instances {
+            let render_pass_id = instance.render_pass_descriptor_id;
+            if !self
+                .runner_state()
+                .render_pass_reflections
+                .contains_key(&render_pass_id)
+            {
+                let shader_program = self
+                    .borrow_render_pass_descriptor(render_pass_id)
+                    .shader_program;
+                let reflection = self.shader_program_reflection(shader_program)?;
+                self.runner_state_mut()
+                    .render_pass_reflections
+                    .insert(render_pass_id, reflection);
+            }
+
+            self.runner_state().render_pass_reflections[&render_pass_id]
+                .validate_bindings(instance)
+                .map_err(Error::ShaderValidation)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl ShaderReflection {
+    pub fn vertex_input(&self, location: i32) -> Option<&ShaderVertexInput> {
+        self.vertex_inputs.iter().find(|i| i.location == location)
+    }
+
+    pub fn uniform_block(&self, name: &str) -> Option<&ShaderUniformBlock> {
+        self.uniform_blocks.iter().find(|b| b.name == name)
+    }
+
+    /// Every shader input must be fed by a buffer attribute of the compatible type.
+    /// Buffer attributes that the shader doesn't read are allowed
+    pub fn validate_vertex_attributes(
+        &self,
+        per_vertex_attributes: &[VertexAttributeDescriptor],
+        per_instance_attributes: &[VertexAttributeDescriptor],
+    ) -> Result<(), ShaderValidationError> {
+        let attributes = per_vertex_attributes.iter().chain(per_instance_attributes);
+
+        let mut locations = Vec::new();
+        for attribute in attributes.clone() {
+            let first = attribute.location;
+            let last = first + location_count(attribute.field_type) - 1;
+            if locations.iter().any(|&(f, l)| first <= l && f <= last) {
+                return Err(ShaderValidationError::DuplicateVertexAttribute {
+                    location: first,
+                });
+            }
+            locations.push((first, last));
+        }
+
+        for input in &self.vertex_inputs {
+            let attribute = attributes.clone().find(|a| a.location == input.location);
+            match attribute {
+                None => {
+                    return Err(ShaderValidationError::MissingVertexAttribute {
+                        name: input.name.clone(),
+                        location: input.location,
+                        shader_type: input.variable_type,
+                    })
+                }
+                Some(a) if !is_compatible(input.variable_type, a.field_type) => {
+                    return Err(ShaderValidationError::VertexAttributeTypeMismatch {
+                        name: input.name.clone(),
+                        location: input.location,
+                        shader_type: input.variable_type,
+                        buffer_type: a.field_type,
+                    })
+                }
+                Some(_) => {}
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn validate_bindings(
+        &self,
+        instance: &RenderPassInstance,
+    ) -> Result<(), ShaderValidationError> {
+        let uniform_bindings = [
+            (
+                ShaderType::Vertex,
+                instance.uniform_vertex_buffers,
+                instance.uniform_vertex_buffer_count,
+            ),
+            (
+                ShaderType::Fragment,
+                instance.uniform_fragment_buffers,
+                instance.uniform_fragment_buffer_count,
+            ),
+        ];
+
+        for (shader_type, ptr, count) in uniform_bindings {
+            for buffer in unsafe { bindings(ptr, count) } {
+                if !self.uniform_blocks.iter().any(|b| {
+                    b.shader_type == shader_type && b.binding == buffer.shader_binding
+                }) {
+                    return Err(ShaderValidationError::UnknownUniformBinding {
+                        shader_type,
+                        binding: buffer.shader_binding,
+                    });
+                }
+            }
+        }
+
+        let textures = unsafe {
+            bindings(instance.fragment_textures, instance.fragment_texture_count)
+        };
+        for texture in textures {
+            let binding = texture.shader_binding;
+            if !self
+                .textures
+                .iter()
+                .any(|t| t.shader_type == ShaderType::Fragment && t.binding == binding)
+            {
+                return Err(ShaderValidationError::UnknownTextureBinding { binding });
+            }
+        }
+
+        Ok(())
+    }
+
+    unsafe fn from_division(
+        reflection: &DivisionShaderReflection,
+    ) -> Result<ShaderReflection, Error> {
+        let unknown_type =
+            |value| Error::Core(format!("Unknown shader reflection type `{value}`"));
+
+        Ok(ShaderReflection {
+            vertex_inputs: slice(reflection.vertex_inputs, reflection.vertex_input_count)
+                .iter()
+                .map(|i| {
+                    Ok(ShaderVertexInput {
+                        name: string(i.name),
+                        variable_type: ShaderVariableType::try_from(i.variable_type)
+                            .map_err(unknown_type)?,
+                        location: i.location,
+                    })
+                })
+                .collect::<Result<_, Error>>()?,
+            uniform_blocks: slice(
+                reflection.uniform_blocks,
+                reflection.uniform_block_count,
+            )
+            .iter()
+            .map(|b| {
+                Ok(ShaderUniformBlock {
+                    name: string(b.name),
+                    shader_type: ShaderType::try_from(b.shader_type)
+                        .map_err(unknown_type)?,
+                    binding: b.binding,
+                    size_bytes: b.size_bytes as usize,
+                })
+            })
+            .collect::<Result<_, Error>>()?,
+            textures: slice(reflection.textures, reflection.texture_count)
+                .iter()
+                .map(|t| {
+                    Ok(ShaderTexture {
+                        name: string(t.name),
+                        shader_type: ShaderType::try_from(t.shader_type)
+                            .map_err(unknown_type)?,
+                        binding: t.binding,
+                    })
+                })
+                .collect::<Result<_, Error>>()?,
+        })
+    }
+}
+
+impl Display for ShaderValidationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ShaderValidationError::MissingVertexAttribute {
+                name,
+                location,
+                shader_type,
+            } => write!(
+                f,
+                "The shader input `{name}` of type {shader_type:?} at location \
+                 {location} has no matching vertex buffer attribute"
+            ),
+            ShaderValidationError::VertexAttributeTypeMismatch {
+                name,
+                location,
+                shader_type,
+                buffer_type,
+            } => write!(
+                f,
+                "The shader input `{name}` at location {location} is {shader_type:?}, \
+                 but the vertex buffer attribute is {buffer_type:?}"
+            ),
+            ShaderValidationError::DuplicateVertexAttribute { location } => write!(
+                f,
+                "The vertex buffer has more than one attribute at location {location}"
+            ),
+            ShaderValidationError::UnknownUniformBinding {
+                shader_type,
+                binding,
+            } => write!(
+                f,
+                "The {shader_type:?} shader has no uniform block at binding {binding}"
+            ),
+            ShaderValidationError::UnknownTextureBinding { binding } => write!(
+                f,
+                "The Fragment shader has no texture at binding {binding}"
+            ),
+        }
+    }
+}
+
+/// Normalized integer attributes are read by shaders as float vectors
+fn is_compatible(
+    shader_type: ShaderVariableType,
+    buffer_type: ShaderVariableType,
+) -> bool {
+    match buffer_type {
+        ShaderVariableType::UByte4Normalized | ShaderVariableType::UShort4Normalized => {
+            shader_type == ShaderVariableType::FVec4
+        }
+        ShaderVariableType::UShort2Normalized => shader_type == ShaderVariableType::FVec2,
+        _ => shader_type == buffer_type,
+    }
+}
+
+fn location_count(variable_type: ShaderVariableType) -> i32 {
+    match variable_type {
+        ShaderVariableType::FMat4x4 => 4,
+        _ => 1,
+    }
+}
+
+unsafe fn slice<'a, T>(ptr: *const T, count: i32) -> &'a [T] {
+    if ptr.is_null() || count <= 0 {
+        return &[];
+    }
+
+    std::slice::from_raw_parts(ptr, count as usize)
+}
+
+unsafe fn bindings<'a>(ptr: *const IdWithBinding, count: i32) -> &'a [IdWithBinding] {
+    slice(ptr, count)
+}
+
+unsafe fn string(ptr: *const c_char) -> String {
+    if ptr.is_null() {
+        return String::new();
+    }
+
+    CStr::from_ptr(ptr).to_string_lossy().into_owned()
+}
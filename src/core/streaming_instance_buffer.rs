@@ -0,0 +1,232 @@
+use std::ops::Range;
+
+use super::{
+    context::Error, Context, DivisionId, RenderPassDescriptor, RenderTopology,
+    VertexBufferSize, VertexData,
+};
+
+/// Instance data that is rebuilt every frame.
+///
+/// Frames are written into a ring of vertex buffers, so a frame doesn't overwrite
+/// the instances that the GPU may still read for the previous ones.
+/// Each slot of the ring has its own vertex buffer with the same static vertex
+/// and index data, and its own render pass descriptor, so passes must be created
+/// with `render_pass_descriptor` after `begin_frame`
+pub struct StreamingInstanceBuffer<TVertex, TInstance> {
+    slots: Vec<StreamingSlot>,
+    ring: StreamingRing,
+    per_vertex_data: Vec<TVertex>,
+    vertex_indices: Vec<u32>,
+    instances: Vec<TInstance>,
+}
+
+struct StreamingSlot {
+    vertex_buffer_id: DivisionId,
+    render_pass_descriptor_id: DivisionId,
+}
+
+/// Slot rotation and upload bookkeeping of the ring, apart from the GPU resources
+pub(crate) struct StreamingRing {
+    slot_capacities: Vec<usize>,
+    current_slot: usize,
+    flushed_count: usize,
+}
+
+pub(crate) struct StreamingFlush {
+    /// New instance capacity of the current slot. The slot vertex buffer must be
+    /// resized and its static data written again
+    pub resized_capacity: Option<usize>,
+    /// Instances to upload, they are marked flushed with `mark_flushed`
+    pub upload_range: Range<usize>,
+}
+
+impl<TVertex, TInstance> StreamingInstanceBuffer<TVertex, TInstance>
+where
+    TVertex: VertexData + Copy,
+    TInstance: VertexData + Copy,
+{
+    pub const DEFAULT_SLOT_COUNT: usize = 3;
+
+    /// `make_descriptor` is called once per slot with the slot vertex buffer id
+    pub fn new(
+        context: &mut Context,
+        per_vertex_data: &[TVertex],
+        vertex_indices: &[u32],
+        topology: RenderTopology,
+        instance_capacity: usize,
+        make_descriptor: impl Fn(DivisionId) -> RenderPassDescriptor,
+    ) -> Result<Self, Error> {
+        Self::with_slot_count(
+            context,
+            per_vertex_data,
+            vertex_indices,
+            topology,
+            instance_capacity,
+            Self::DEFAULT_SLOT_COUNT,
+            make_descriptor,
+        )
+    }
+
+    pub fn with_slot_count(
+        context: &mut Context,
+        per_vertex_data: &[TVertex],
+        vertex_indices: &[u32],
+        topology: RenderTopology,
+        instance_capacity: usize,
+        slot_count: usize,
+        make_descriptor: impl Fn(DivisionId) -> RenderPassDescriptor,
+    ) -> Result<Self, Error> {
+        assert!(slot_count > 0, "A streaming buffer needs at least one slot");
+
+        let mut buffer = StreamingInstanceBuffer {
+            slots: Vec::with_capacity(slot_count),
+            ring: StreamingRing::new(slot_count, instance_capacity),
+            per_vertex_data: per_vertex_data.to_vec(),
+            vertex_indices: vertex_indices.to_vec(),
+            instances: Vec::with_capacity(instance_capacity),
+        };
+
+        for _ in 0..slot_count {
+            let vertex_buffer_id = context.create_vertex_buffer::<TVertex, TInstance>(
+                buffer.buffer_size(instance_capacity),
+                topology,
+            )?;
+            buffer.write_static_data(context, vertex_buffer_id);
+
+            let render_pass_descriptor_id = context
+                .create_render_pass_descriptor(&make_descriptor(vertex_buffer_id))?;
+
+            buffer.slots.push(StreamingSlot {
+                vertex_buffer_id,
+                render_pass_descriptor_id,
+            });
+        }
+
+        Ok(buffer)
+    }
+
+    /// Switches to the next slot of the ring and drops the instances of the last frame
+    pub fn begin_frame(&mut self) {
+        self.ring.advance();
+        self.instances.clear();
+    }
+
+    /// Appends the instance to the current frame and returns its index,
+    /// which is the `first_instance` for the render pass that starts with it
+    pub fn push(&mut self, instance: TInstance) -> u32 {
+        self.instances.push(instance);
+        (self.instances.len() - 1) as u32
+    }
+
+    #[inline]
+    pub fn instance_count(&self) -> u32 {
+        self.instances.len() as u32
+    }
+
+    #[inline]
+    pub fn render_pass_descriptor(&self) -> DivisionId {
+        self.slots[self.ring.current_slot()].render_pass_descriptor_id
+    }
+
+    /// Descriptors of all slots, e.g. to swap their shader program
+    pub fn render_pass_descriptors(&self) -> impl Iterator<Item = DivisionId> + '_ {
+        self.slots.iter().map(|s| s.render_pass_descriptor_id)
+    }
+
+    #[inline]
+    pub fn vertex_buffer(&self) -> DivisionId {
+        self.slots[self.ring.current_slot()].vertex_buffer_id
+    }
+
+    /// Uploads the instances pushed since the last flush. Must be called before
+    /// the passes that use them are drawn.
+    /// If the slot is too small, it's resized, and everything is uploaded again
+    pub fn flush(&mut self, context: &mut Context) -> Result<(), Error> {
+        let vertex_buffer_id = self.vertex_buffer();
+        let flush = self.ring.flush(self.instances.len());
+
+        if let Some(new_capacity) = flush.resized_capacity {
+            let new_size = self.buffer_size(new_capacity);
+            context.vertex_buffer_resize(vertex_buffer_id, new_size);
+            self.write_static_data(context, vertex_buffer_id);
+        }
+
+        if !flush.upload_range.is_empty() {
+            let mut range = context.vertex_buffer_instance_range::<TInstance>(
+                vertex_buffer_id,
+                flush.upload_range.clone(),
+            )?;
+            range.write(0, &self.instances[flush.upload_range.clone()]);
+            self.ring.mark_flushed(flush.upload_range.end);
+        }
+
+        Ok(())
+    }
+
+    pub fn delete(&mut self, context: &mut Context) {
+        for slot in self.slots.drain(..) {
+            context.delete_render_pass_descriptor(slot.render_pass_descriptor_id);
+            context.delete_vertex_buffer(slot.vertex_buffer_id);
+        }
+    }
+
+    fn buffer_size(&self, instance_capacity: usize) -> VertexBufferSize {
+        VertexBufferSize {
+            vertex_count: self.per_vertex_data.len() as u32,
+            index_count: self.vertex_indices.len() as u32,
+            instance_count: instance_capacity as u32,
+        }
+    }
+
+    fn write_static_data(&self, context: &mut Context, vertex_buffer_id: DivisionId) {
+        let data = context.vertex_buffer_data::<TVertex, TInstance>(vertex_buffer_id);
+        data.per_vertex_data.copy_from_slice(&self.per_vertex_data);
+        data.vertex_indices.copy_from_slice(&self.vertex_indices);
+    }
+}
+
+impl StreamingRing {
+    pub fn new(slot_count: usize, instance_capacity: usize) -> StreamingRing {
+        StreamingRing {
+            slot_capacities: vec![instance_capacity; slot_count],
+            current_slot: 0,
+            flushed_count: 0,
+        }
+    }
+
+    #[inline]
+    pub fn current_slot(&self) -> usize {
+        self.current_slot
+    }
+
+    /// Switches to the next slot, nothing of it is flushed for the new frame
+    pub fn advance(&mut self) {
+        self.current_slot = (self.current_slot + 1) % self.slot_capacities.len();
+        self.flushed_count = 0;
+    }
+
+    /// Plans the upload of `instance_count` instances of the current frame.
+    /// A slot that is too small at least doubles, and everything is uploaded again
+    pub fn flush(&mut self, instance_count: usize) -> StreamingFlush {
+        let capacity = &mut self.slot_capacities[self.current_slot];
+        let mut resized_capacity = None;
+
+        if instance_count > *capacity {
+            *capacity = std::cmp::max(instance_count, *capacity * 2);
+            resized_capacity = Some(*capacity);
+            self.flushed_count = 0;
+        }
+
+        StreamingFlush {
+            resized_capacity,
+            upload_range: self.flushed_count..std::cmp::max(
+                self.flushed_count,
+                instance_count,
+            ),
+        }
+    }
+
+    pub fn mark_flushed(&mut self, instance_count: usize) {
+        self.flushed_count = instance_count;
+    }
+}
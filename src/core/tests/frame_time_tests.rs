@@ -0,0 +1,65 @@
+use std::time::{Duration, Instant};
+
+use crate::core::{frame_time::FrameTimer, FixedTimestep};
+
+#[test]
+fn first_frame_has_zero_delta() {
+    let mut timer = FrameTimer::new(None);
+    timer.begin_frame(Instant::now());
+
+    let frame_time = timer.frame_time();
+    assert_eq!(frame_time.delta, Duration::ZERO);
+    assert_eq!(frame_time.total, Duration::ZERO);
+    assert_eq!(frame_time.frame_index, 0);
+    assert!(timer.next_fixed_step().is_none());
+}
+
+#[test]
+fn frame_time_accumulates() {
+    let start = Instant::now();
+    let mut timer = FrameTimer::new(None);
+    timer.begin_frame(start);
+    timer.begin_frame(start + Duration::from_millis(16));
+    timer.begin_frame(start + Duration::from_millis(40));
+
+    let frame_time = timer.frame_time();
+    assert_eq!(frame_time.delta, Duration::from_millis(24));
+    assert_eq!(frame_time.total, Duration::from_millis(40));
+    assert_eq!(frame_time.frame_index, 2);
+    assert_eq!(frame_time.interpolation_alpha, 0.);
+}
+
+#[test]
+fn fixed_steps_consume_accumulator() {
+    let start = Instant::now();
+    let mut timer = FrameTimer::new(Some(FixedTimestep::new(Duration::from_millis(10))));
+    timer.begin_frame(start);
+    timer.begin_frame(start + Duration::from_millis(25));
+
+    let first = timer.next_fixed_step().unwrap();
+    let second = timer.next_fixed_step().unwrap();
+
+    assert_eq!(first.step_index, 0);
+    assert_eq!(second.step_index, 1);
+    assert_eq!(second.total, Duration::from_millis(10));
+    assert!(timer.next_fixed_step().is_none());
+    assert!((timer.frame_time().interpolation_alpha - 0.5).abs() < 1e-6);
+}
+
+#[test]
+fn fixed_steps_are_limited_per_frame() {
+    let start = Instant::now();
+    let mut timer = FrameTimer::new(Some(
+        FixedTimestep::new(Duration::from_millis(10)).with_max_steps_per_frame(2),
+    ));
+    timer.begin_frame(start);
+    timer.begin_frame(start + Duration::from_millis(55));
+
+    let mut steps = 0;
+    while timer.next_fixed_step().is_some() {
+        steps += 1;
+    }
+
+    assert_eq!(steps, 2);
+    assert!((timer.frame_time().interpolation_alpha - 0.5).abs() < 1e-6);
+}
@@ -0,0 +1,28 @@
+use division_math::Vector2;
+
+use crate::core::framebuffer::check_framebuffer_region;
+
+#[test]
+fn region_inside_framebuffer_is_accepted() {
+    let size = Vector2::new(800., 600.);
+
+    assert!(check_framebuffer_region(0, 0, 800, 600, size).is_ok());
+    assert!(check_framebuffer_region(790, 590, 10, 10, size).is_ok());
+    assert!(check_framebuffer_region(800, 600, 0, 0, size).is_ok());
+}
+
+#[test]
+fn region_outside_framebuffer_is_rejected() {
+    let size = Vector2::new(800., 600.);
+
+    assert!(check_framebuffer_region(0, 0, 801, 600, size).is_err());
+    assert!(check_framebuffer_region(0, 1, 800, 600, size).is_err());
+}
+
+#[test]
+fn overflowing_region_is_rejected() {
+    let size = Vector2::new(800., 600.);
+
+    assert!(check_framebuffer_region(usize::MAX, 0, 2, 1, size).is_err());
+    assert!(check_framebuffer_region(0, 1, 1, usize::MAX, size).is_err());
+}
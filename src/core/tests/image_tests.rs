@@ -0,0 +1,85 @@
+use std::path::Path;
+
+use crate::core::{ImageFileFormat, ImageSettings, TextureFormat};
+
+#[test]
+fn file_format_from_extension() {
+    assert_eq!(
+        ImageFileFormat::from_path(Path::new("a/b.png")),
+        Some(ImageFileFormat::Png)
+    );
+    assert_eq!(
+        ImageFileFormat::from_path(Path::new("b.bmp")),
+        Some(ImageFileFormat::Bmp)
+    );
+    assert_eq!(
+        ImageFileFormat::from_path(Path::new("b.tga")),
+        Some(ImageFileFormat::Tga)
+    );
+    assert_eq!(
+        ImageFileFormat::from_path(Path::new("b.hdr")),
+        Some(ImageFileFormat::Hdr)
+    );
+}
+
+#[test]
+fn file_format_jpg_aliases_use_default_quality() {
+    let expected = Some(ImageFileFormat::Jpg {
+        quality: ImageFileFormat::DEFAULT_JPG_QUALITY,
+    });
+
+    assert_eq!(ImageFileFormat::from_path(Path::new("b.jpg")), expected);
+    assert_eq!(ImageFileFormat::from_path(Path::new("b.jpeg")), expected);
+}
+
+#[test]
+fn file_format_extension_is_case_insensitive() {
+    assert_eq!(
+        ImageFileFormat::from_path(Path::new("B.PNG")),
+        Some(ImageFileFormat::Png)
+    );
+    assert_eq!(
+        ImageFileFormat::from_path(Path::new("b.JpEg")),
+        Some(ImageFileFormat::Jpg {
+            quality: ImageFileFormat::DEFAULT_JPG_QUALITY
+        })
+    );
+}
+
+#[test]
+fn file_format_unknown_or_missing_extension() {
+    assert_eq!(ImageFileFormat::from_path(Path::new("b.gif")), None);
+    assert_eq!(ImageFileFormat::from_path(Path::new("b")), None);
+    assert_eq!(ImageFileFormat::from_path(Path::new("png")), None);
+}
+
+#[test]
+fn image_settings_accept_only_8_bit_rgba_order_formats() {
+    for format in [
+        TextureFormat::R8Uint,
+        TextureFormat::RG16Uint,
+        TextureFormat::RGB24Uint,
+        TextureFormat::RGBA32Uint,
+        TextureFormat::RGBA32UintSrgb,
+    ] {
+        assert!(ImageSettings::try_new(format, false).is_ok(), "{format:?}");
+    }
+
+    for format in [
+        TextureFormat::BGRA32Uint,
+        TextureFormat::BGRA32UintSrgb,
+        TextureFormat::R16Float,
+        TextureFormat::RGBA64Float,
+        TextureFormat::RGBA128Float,
+        TextureFormat::Depth32Float,
+        TextureFormat::Depth24Stencil8,
+    ] {
+        assert!(ImageSettings::try_new(format, false).is_err(), "{format:?}");
+    }
+}
+
+#[test]
+#[should_panic]
+fn image_settings_panic_on_unsupported_format() {
+    ImageSettings::with_texture_format(TextureFormat::RGBA128Float);
+}
@@ -0,0 +1,167 @@
+use std::time::Duration;
+
+use crate::core::{
+    input::{InputState, Keycode, MouseButton},
+    input_map::{AxisBinding, Binding, Error, InputMap, InputTrigger},
+};
+
+use super::make_input;
+
+const CONFIG: &str = "\
+# Movement
+action jump = Space, Mouse:Left
+action save = LCmdLCtrl+S
+axis move_x = D | A, RightArrow | LeftArrow
+";
+
+#[test]
+fn config_roundtrip_as_expected() {
+    let map = InputMap::from_config_str(CONFIG).unwrap();
+
+    assert_eq!(
+        map.action_bindings("jump").unwrap(),
+        &[
+            Binding::key(Keycode::Space),
+            Binding::mouse_button(MouseButton::Left)
+        ]
+    );
+    assert_eq!(
+        map.action_bindings("save").unwrap(),
+        &[Binding::key(Keycode::S).with_modifier(Keycode::LCmdLCtrl)]
+    );
+
+    let restored = InputMap::from_config_str(&map.to_config_string()).unwrap();
+    assert_eq!(restored.to_config_string(), map.to_config_string());
+}
+
+#[test]
+fn parse_error_reports_line() {
+    let result = InputMap::from_config_str("action jump = Space\naction fire = Nope\n");
+
+    assert!(matches!(result, Err(Error::Parse { line: 2, .. })));
+}
+
+#[test]
+fn rebind_detects_conflicts() {
+    let mut map = InputMap::from_config_str(CONFIG).unwrap();
+
+    let result = map.rebind_action(
+        "jump",
+        &Binding::key(Keycode::Space),
+        Binding::key(Keycode::D),
+    );
+    assert!(matches!(result, Err(Error::Conflict(c)) if c.used_by == ["move_x"]));
+    assert!(map.conflicts().is_empty());
+
+    map.bind_action("dash", Binding::key(Keycode::Space));
+    let conflicts = map.conflicts();
+    assert_eq!(conflicts.len(), 1);
+    assert_eq!(conflicts[0].used_by, ["jump", "dash"]);
+
+    map.rebind_action("dash", &Binding::key(Keycode::Space), Binding::key(Keycode::E))
+        .unwrap();
+    assert!(map.conflicts().is_empty());
+}
+
+#[test]
+fn rebind_checks_same_named_action_and_axis() {
+    let mut map = InputMap::from_config_str(CONFIG).unwrap();
+    map.bind_axis(
+        "jump",
+        AxisBinding::new(Binding::key(Keycode::W), Binding::key(Keycode::S)),
+    );
+
+    let result = map.rebind_action(
+        "jump",
+        &Binding::key(Keycode::Space),
+        Binding::key(Keycode::W),
+    );
+    assert!(matches!(result, Err(Error::Conflict(c)) if c.used_by == ["jump"]));
+}
+
+#[test]
+fn rebind_axis_as_expected() {
+    let mut map = InputMap::from_config_str(CONFIG).unwrap();
+    let old = AxisBinding::new(Binding::key(Keycode::D), Binding::key(Keycode::A));
+
+    let result = map.rebind_axis(
+        "move_x",
+        &old,
+        AxisBinding::new(Binding::key(Keycode::L), Binding::key(Keycode::Space)),
+    );
+    assert!(matches!(result, Err(Error::Conflict(c)) if c.used_by == ["jump"]));
+
+    let new = AxisBinding::new(Binding::key(Keycode::L), Binding::key(Keycode::J));
+    map.rebind_axis("move_x", &old, new.clone()).unwrap();
+    assert_eq!(map.axis_bindings("move_x").unwrap()[0], new);
+
+    let result = map.rebind_axis("move_x", &old, old.clone());
+    assert!(matches!(result, Err(Error::AxisBindingNotFound(_))));
+
+    map.unbind_axis("move_x", &new).unwrap();
+    assert_eq!(map.axis_bindings("move_x").unwrap().len(), 1);
+}
+
+#[test]
+fn modifier_order_does_not_matter() {
+    let shift_ctrl: Binding = "LShift+LCmdLCtrl+S".parse().unwrap();
+    let ctrl_shift = Binding::key(Keycode::S)
+        .with_modifier(Keycode::LCmdLCtrl)
+        .with_modifier(Keycode::LShift)
+        .with_modifier(Keycode::LCmdLCtrl);
+    assert_eq!(shift_ctrl, ctrl_shift);
+    assert_eq!(
+        Binding::new(
+            [Keycode::LShift, Keycode::LCmdLCtrl, Keycode::LShift],
+            InputTrigger::Key(Keycode::S),
+        ),
+        ctrl_shift
+    );
+    assert_eq!(ctrl_shift.modifiers(), [Keycode::LShift, Keycode::LCmdLCtrl]);
+
+    let mut map = InputMap::new();
+    map.bind_action("save", shift_ctrl);
+    map.bind_action("save_all", "LCmdLCtrl+LShift+S".parse().unwrap());
+    assert_eq!(map.conflicts()[0].used_by, ["save", "save_all"]);
+}
+
+#[test]
+fn evaluation_as_expected() {
+    let mut map = InputMap::from_config_str(CONFIG).unwrap();
+    map.bind_axis(
+        "move_y",
+        AxisBinding::new(Binding::key(Keycode::W), Binding::key(Keycode::S)),
+    );
+    let mut state = InputState::new();
+
+    state.update(&make_input(&[Keycode::Space, Keycode::S], 0, 0, 0), Duration::ZERO);
+    assert!(map.action_just_pressed("jump", &state));
+    assert!(!map.is_action_down("save", &state));
+    assert_eq!(map.axis_value("move_y", &state), -1.);
+
+    state.update(
+        &make_input(
+            &[Keycode::LCmdLCtrl, Keycode::S, Keycode::D, Keycode::RightArrow],
+            0,
+            0,
+            0,
+        ),
+        Duration::from_millis(16),
+    );
+    assert!(map.action_just_released("jump", &state));
+    assert!(map.is_action_down("save", &state));
+    assert_eq!(map.axis_value("move_x", &state), 1.);
+    assert_eq!(map.axis_value("unknown", &state), 0.);
+}
+
+#[test]
+fn error_display_as_expected() {
+    let error = InputMap::from_config_str("action jump = Space\naction").err().unwrap();
+    assert_eq!(error.to_string(), "Line 2: Expected `=`");
+
+    let mut map = InputMap::from_config_str(CONFIG).unwrap();
+    let error = map
+        .rebind_action("jump", &Binding::key(Keycode::Space), Binding::key(Keycode::D))
+        .unwrap_err();
+    assert_eq!(error.to_string(), "Binding conflict: `D` is used by move_x");
+}
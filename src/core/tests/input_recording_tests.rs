@@ -0,0 +1,88 @@
+use std::time::Duration;
+
+use crate::core::{
+    ffi::input::{DivisionInput, DivisionTextInput},
+    frame_time::FrameTimer,
+    input::ScrollDeltaUnit,
+    input_recording::{InputRecording, InputRecordingWriter},
+};
+
+use super::make_input;
+
+fn recorded_input(pos_x: i32, scroll_y: f32, text: &[u32]) -> DivisionInput {
+    let mut input = make_input(&[], 0b101, pos_x, 7);
+    input.mouse.scroll_delta_y = scroll_y;
+    input.mouse.scroll_delta_unit = ScrollDeltaUnit::Pixel.into();
+    input.keyboard.key_state_mask = [1, 0, 1 << 31, 0];
+    input.keyboard.key_repeat_mask = [0, 2, 0, 0];
+    input.text = DivisionTextInput {
+        characters: text.as_ptr(),
+        character_count: text.len() as u32,
+    };
+    input
+}
+
+#[test]
+fn recording_roundtrip_as_expected() {
+    let text = ['h' as u32, 'ï' as u32];
+    let mut recording = InputRecording::new();
+    recording.push_frame(Duration::ZERO, &recorded_input(1, 0., &[]));
+    recording.push_frame(
+        Duration::from_nanos(16_666_667),
+        &recorded_input(-5, 0.1, &text),
+    );
+
+    let restored =
+        InputRecording::from_recording_str(&recording.to_recording_string()).unwrap();
+    assert_eq!(restored.to_recording_string(), recording.to_recording_string());
+
+    let frame = &restored.frames()[1];
+    let input = frame.to_input();
+    assert_eq!(frame.delta, Duration::from_nanos(16_666_667));
+    assert_eq!(input.mouse.pos_x, -5);
+    assert_eq!(input.mouse.scroll_delta_y, 0.1);
+    assert_eq!(input.mouse.scroll_unit(), Some(ScrollDeltaUnit::Pixel));
+    assert_eq!(input.keyboard.key_state_mask, [1, 0, 1 << 31, 0]);
+    assert_eq!(input.text.characters().collect::<String>(), "hï");
+}
+
+#[test]
+fn malformed_recording_is_rejected() {
+    assert!(InputRecording::from_recording_str("0 0 0").is_err());
+
+    let recording = InputRecording::from_recording_str(
+        "division-input-recording 1\n0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 -\n0 0\n",
+    );
+    assert!(recording.is_err());
+}
+
+#[test]
+fn replayed_deltas_drive_frame_time() {
+    let mut timer = FrameTimer::new(None);
+    timer.begin_frame_with_delta(Duration::from_millis(100));
+    timer.begin_frame_with_delta(Duration::from_millis(16));
+    timer.begin_frame_with_delta(Duration::from_millis(20));
+
+    let frame_time = timer.frame_time();
+    assert_eq!(frame_time.delta, Duration::from_millis(20));
+    assert_eq!(frame_time.total, Duration::from_millis(36));
+    assert_eq!(frame_time.frame_index, 2);
+}
+
+#[test]
+fn writer_output_matches_recording() {
+    let text = ['x' as u32];
+    let mut recording = InputRecording::new();
+    let mut writer = InputRecordingWriter::new(Vec::new()).unwrap();
+    for (delta, input) in [
+        (Duration::ZERO, recorded_input(1, 0., &[])),
+        (Duration::from_millis(16), recorded_input(3, 2., &text)),
+    ] {
+        recording.push_frame(delta, &input);
+        writer.write_frame(delta, &input).unwrap();
+    }
+
+    let written = String::from_utf8(writer.into_inner()).unwrap();
+    assert_eq!(written, recording.to_recording_string());
+    assert_eq!(InputRecording::from_recording_str(&written).unwrap().frames().len(), 2);
+}
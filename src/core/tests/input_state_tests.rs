@@ -0,0 +1,108 @@
+use std::time::Duration;
+
+use crate::core::{
+    ffi::input::DivisionTextInput,
+    input::{InputState, Keycode, MouseButton, ScrollDeltaUnit},
+};
+
+use super::make_input;
+
+#[test]
+fn key_edges_as_expected() {
+    let mut state = InputState::new();
+
+    state.update(&make_input(&[Keycode::Space], 0, 0, 0), Duration::ZERO);
+    assert!(state.just_pressed(Keycode::Space));
+    assert!(!state.just_released(Keycode::Space));
+
+    state.update(&make_input(&[Keycode::Space], 0, 0, 0), Duration::from_millis(16));
+    assert!(!state.just_pressed(Keycode::Space));
+    assert!(state.is_key_down(Keycode::Space));
+
+    state.update(&make_input(&[], 0, 0, 0), Duration::from_millis(32));
+    assert!(state.just_released(Keycode::Space));
+    assert!(!state.is_key_down(Keycode::Space));
+}
+
+#[test]
+fn held_duration_as_expected() {
+    let mut state = InputState::new();
+
+    state.update(&make_input(&[], 0, 0, 0), Duration::ZERO);
+    assert_eq!(state.held_duration(Keycode::A), None);
+
+    state.update(&make_input(&[Keycode::A], 1, 0, 0), Duration::from_millis(10));
+    state.update(&make_input(&[Keycode::A], 1, 0, 0), Duration::from_millis(35));
+
+    assert_eq!(state.held_duration(Keycode::A), Some(Duration::from_millis(25)));
+    assert_eq!(
+        state.button_held_duration(MouseButton::Left),
+        Some(Duration::from_millis(25))
+    );
+}
+
+#[test]
+fn keys_down_as_expected() {
+    let mut state = InputState::new();
+    state.update(
+        &make_input(&[Keycode::Q, Keycode::Eject, Keycode::F1], 0, 0, 0),
+        Duration::ZERO,
+    );
+
+    let keys = state.keys_down().collect::<Vec<_>>();
+    assert_eq!(keys, vec![Keycode::Q, Keycode::F1, Keycode::Eject]);
+}
+
+#[test]
+fn mouse_delta_as_expected() {
+    let mut state = InputState::new();
+
+    state.update(&make_input(&[], 0b10, 100, 50), Duration::ZERO);
+    assert_eq!(state.mouse_delta().x, 0.);
+    assert_eq!(state.mouse_delta().y, 0.);
+    assert!(state.button_just_pressed(MouseButton::Right));
+
+    state.update(&make_input(&[], 0, 110, 40), Duration::from_millis(16));
+    assert_eq!(state.mouse_delta().x, 10.);
+    assert_eq!(state.mouse_delta().y, -10.);
+    assert!(state.button_just_released(MouseButton::Right));
+}
+
+#[test]
+fn text_input_is_copied() {
+    let mut state = InputState::new();
+    let characters = ['п' as u32, 'r' as u32, 0xD800, '!' as u32];
+    let mut input = make_input(&[], 0, 0, 0);
+    input.text = DivisionTextInput {
+        characters: characters.as_ptr(),
+        character_count: characters.len() as u32,
+    };
+
+    state.update(&input, Duration::ZERO);
+    assert_eq!(state.text_input(), &['п', 'r', '!']);
+
+    state.update(&make_input(&[], 0, 0, 0), Duration::from_millis(16));
+    assert!(state.text_input().is_empty());
+}
+
+#[test]
+fn buttons_outside_mask_are_not_pressed() {
+    let mut state = InputState::new();
+    state.update(&make_input(&[], u32::MAX, 0, 0), Duration::ZERO);
+
+    assert!(state.is_button_down(MouseButton::Other(31)));
+    assert!(!state.is_button_down(MouseButton::Other(32)));
+    assert!(!state.button_just_pressed(MouseButton::Other(100)));
+    assert_eq!(state.button_held_duration(MouseButton::Other(32)), None);
+}
+
+#[test]
+fn unknown_scroll_unit_is_lines() {
+    let mut state = InputState::new();
+    let mut input = make_input(&[], 0, 0, 0);
+    input.mouse.scroll_delta_unit = 7;
+    state.update(&input, Duration::ZERO);
+
+    assert_eq!(input.mouse.scroll_unit(), None);
+    assert_eq!(state.scroll_delta().unit, ScrollDeltaUnit::Line);
+}
@@ -0,0 +1,54 @@
+mod frame_time_tests;
+mod framebuffer_tests;
+mod image_tests;
+mod input_map_tests;
+mod input_recording_tests;
+mod input_state_tests;
+mod shader_diagnostics_tests;
+mod shader_preprocessor_tests;
+mod shader_reflection_tests;
+mod streaming_instance_buffer_tests;
+mod texture_tests;
+mod uniform_block_tests;
+mod vertex_data_tests;
+mod window_event_tests;
+
+use crate::core::{
+    ffi::input::{
+        DivisionInput, DivisionKeyboardInput, DivisionMouseInput, DivisionTextInput,
+    },
+    input::{Keycode, ScrollDeltaUnit},
+};
+
+/// Input with the keys and mouse buttons of the mask held down
+fn make_input(
+    keys: &[Keycode],
+    mouse_mask: u32,
+    pos_x: i32,
+    pos_y: i32,
+) -> DivisionInput {
+    let mut key_state_mask = [0u32; 4];
+    for k in keys {
+        let k = *k as u32;
+        key_state_mask[(k / 32) as usize] |= 1 << (k % 32);
+    }
+
+    DivisionInput {
+        mouse: DivisionMouseInput {
+            pos_x,
+            pos_y,
+            mouse_button_state_mask: mouse_mask,
+            scroll_delta_x: 0.,
+            scroll_delta_y: 0.,
+            scroll_delta_unit: ScrollDeltaUnit::Line.into(),
+        },
+        keyboard: DivisionKeyboardInput {
+            key_state_mask,
+            key_repeat_mask: [0; 4],
+        },
+        text: DivisionTextInput {
+            characters: std::ptr::null(),
+            character_count: 0,
+        },
+    }
+}
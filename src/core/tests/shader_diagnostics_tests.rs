@@ -0,0 +1,90 @@
+use std::path::{Path, PathBuf};
+
+use crate::core::{
+    DiagnosticSeverity, ShaderCompileError, ShaderDiagnostic, ShaderPreprocessor,
+    ShaderType,
+};
+
+fn diagnostic(
+    severity: DiagnosticSeverity,
+    file: Option<&str>,
+    line: Option<u32>,
+    message: &str,
+) -> ShaderDiagnostic {
+    ShaderDiagnostic {
+        severity,
+        file: file.map(PathBuf::from),
+        line,
+        message: message.to_string(),
+    }
+}
+
+#[test]
+fn parses_glslang_mesa_and_nvidia_logs() {
+    let log = "ERROR: 0:4: 'oops' : undeclared identifier\n\
+               WARNING: 0:2: 'x' : unused\n\
+               ERROR: 1 compilation errors.  No code generated.\n\
+               0:7(12): error: syntax error, unexpected '}'\n\
+               0(9) : warning C7050: \"c\" might be used before being initialized\n\
+               Link failed\n";
+
+    let error = ShaderCompileError::from_glsl_log(ShaderType::Fragment, log, None);
+
+    assert_eq!(
+        error.diagnostics,
+        vec![
+            diagnostic(
+                DiagnosticSeverity::Error,
+                None,
+                Some(4),
+                "'oops' : undeclared identifier"
+            ),
+            diagnostic(DiagnosticSeverity::Warning, None, Some(2), "'x' : unused"),
+            diagnostic(
+                DiagnosticSeverity::Error,
+                None,
+                Some(7),
+                "syntax error, unexpected '}'"
+            ),
+            diagnostic(
+                DiagnosticSeverity::Warning,
+                None,
+                Some(9),
+                "\"c\" might be used before being initialized"
+            ),
+            diagnostic(DiagnosticSeverity::Error, None, None, "Link failed"),
+        ]
+    );
+    assert_eq!(error.errors().count(), 3);
+}
+
+#[test]
+fn lines_are_mapped_through_the_preprocessor() {
+    let shader = ShaderPreprocessor::new()
+        .define("TEXTURED", "1")
+        .process_source(
+            Path::new("rect.frag"),
+            "#version 450 core\nvoid main() {\n    oops;\n}\n",
+        )
+        .unwrap();
+
+    let error = ShaderCompileError::from_glsl_log(
+        ShaderType::Fragment,
+        "0:4(5): error: `oops' undeclared",
+        Some(&shader),
+    );
+
+    assert_eq!(
+        error.diagnostics,
+        vec![diagnostic(
+            DiagnosticSeverity::Error,
+            Some("rect.frag"),
+            Some(3),
+            "`oops' undeclared"
+        )]
+    );
+    assert_eq!(
+        error.to_string(),
+        "Failed to compile the Fragment shader\nrect.frag:3: error: `oops' undeclared"
+    );
+}
@@ -0,0 +1,158 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use crate::core::{PreprocessError, ShaderPreprocessor};
+
+fn make_shader_dir(name: &str, files: &[(&str, &str)]) -> PathBuf {
+    let dir = std::env::temp_dir()
+        .join("division_engine_shader_preprocessor_tests")
+        .join(name);
+    let _ = fs::remove_dir_all(&dir);
+
+    for (path, source) in files {
+        let path = dir.join(path);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, source).unwrap();
+    }
+
+    dir
+}
+
+#[test]
+fn includes_are_relative_to_the_including_file() {
+    let dir = make_shader_dir(
+        "relative_includes",
+        &[
+            (
+                "canvas/rect.vert",
+                "#version 450 core\n#include \"common/math.glsl\"\nvoid main() {}\n",
+            ),
+            (
+                "canvas/common/math.glsl",
+                "#include \"../screen.glsl\"\nfloat twice(float v) { return v * 2.; }\n",
+            ),
+            ("canvas/screen.glsl", "uniform vec2 screenSize;\n"),
+        ],
+    );
+
+    let shader = ShaderPreprocessor::new()
+        .define("TEXTURED", "1")
+        .process_file(&dir.join("canvas/rect.vert"))
+        .unwrap();
+
+    assert_eq!(
+        shader.source,
+        "#version 450 core\n\
+         #define TEXTURED 1\n\
+         uniform vec2 screenSize;\n\
+         float twice(float v) { return v * 2.; }\n\
+         void main() {}\n"
+    );
+    assert_eq!(shader.files().len(), 3);
+}
+
+#[test]
+fn lines_map_to_original_files() {
+    let dir = make_shader_dir(
+        "line_mapping",
+        &[
+            (
+                "shader.frag",
+                "#version 450 core\n#include \"lib.glsl\"\n\nvoid main() { oops }\n",
+            ),
+            ("lib.glsl", "#pragma once\nvec4 tint;\n"),
+        ],
+    );
+    let path = dir.join("shader.frag");
+
+    let shader = ShaderPreprocessor::new()
+        .define("A", "1")
+        .define("B", "2")
+        .process_file(&path)
+        .unwrap();
+
+    let location = shader.source_location(4).unwrap();
+    assert_eq!((location.file, location.line), (dir.join("lib.glsl").as_path(), 2));
+    let location = shader.source_location(6).unwrap();
+    assert_eq!((location.file, location.line), (path.as_path(), 4));
+    assert_eq!(shader.source_location(2).unwrap().line, 1);
+    assert!(shader.source_location(7).is_none());
+}
+
+#[test]
+fn pragma_once_files_are_included_once() {
+    let dir = make_shader_dir(
+        "pragma_once",
+        &[
+            (
+                "shader.vert",
+                "#include \"a.glsl\"\n#include \"b.glsl\"\nvoid main() {}\n",
+            ),
+            ("a.glsl", "#include \"common.glsl\"\nfloat a;\n"),
+            ("b.glsl", "#include \"common.glsl\"\nfloat b;\n"),
+            ("common.glsl", "# pragma once\nfloat common;\n"),
+        ],
+    );
+
+    let shader = ShaderPreprocessor::new()
+        .define("VARIANT", "2")
+        .process_file(&dir.join("shader.vert"))
+        .unwrap();
+
+    assert_eq!(
+        shader.source,
+        "#define VARIANT 2\nfloat common;\nfloat a;\nfloat b;\nvoid main() {}\n"
+    );
+}
+
+#[test]
+fn include_cycles_and_missing_files_are_errors() {
+    let dir = make_shader_dir(
+        "include_errors",
+        &[
+            ("a.glsl", "#include \"b.glsl\"\n"),
+            ("b.glsl", "#include \"a.glsl\"\n"),
+            ("missing.glsl", "#include \"nothing.glsl\"\n"),
+            ("invalid.glsl", "#include nothing.glsl\n"),
+        ],
+    );
+    let preprocessor = ShaderPreprocessor::new();
+
+    assert!(matches!(
+        preprocessor.process_file(&dir.join("a.glsl")),
+        Err(PreprocessError::IncludeCycle { .. })
+    ));
+    assert!(matches!(
+        preprocessor.process_file(&dir.join("missing.glsl")),
+        Err(PreprocessError::Io { path, .. }) if path.ends_with("nothing.glsl")
+    ));
+    assert!(matches!(
+        preprocessor.process_file(&dir.join("invalid.glsl")),
+        Err(PreprocessError::InvalidDirective { line: 1, .. })
+    ));
+}
+
+#[test]
+fn system_includes_are_left_to_the_compiler() {
+    let source = "#include <metal_stdlib>\nusing namespace metal;\n";
+    let shader = ShaderPreprocessor::new()
+        .process_source(Path::new("inline.metal"), source)
+        .unwrap();
+
+    assert_eq!(shader.source, source);
+}
+
+#[test]
+fn sources_without_files_map_to_the_given_path() {
+    let shader = ShaderPreprocessor::new()
+        .process_source(Path::new("inline.vert"), "void main() {}")
+        .unwrap();
+
+    assert_eq!(shader.source, "void main() {}\n");
+    assert_eq!(
+        shader.source_location(1).unwrap().to_string(),
+        "inline.vert:1"
+    );
+}
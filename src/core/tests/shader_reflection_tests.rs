@@ -0,0 +1,97 @@
+use crate::core::{
+    ShaderReflection, ShaderValidationError, ShaderVariableType, ShaderVertexInput,
+    VertexAttributeDescriptor,
+};
+
+fn reflection(inputs: &[(&str, ShaderVariableType, i32)]) -> ShaderReflection {
+    ShaderReflection {
+        vertex_inputs: inputs
+            .iter()
+            .map(|&(name, variable_type, location)| ShaderVertexInput {
+                name: name.to_string(),
+                variable_type,
+                location,
+            })
+            .collect(),
+        ..Default::default()
+    }
+}
+
+fn attribute(field_type: ShaderVariableType, location: i32) -> VertexAttributeDescriptor {
+    VertexAttributeDescriptor {
+        field_type,
+        location,
+    }
+}
+
+#[test]
+fn matching_layout_is_valid() {
+    let reflection = reflection(&[
+        ("position", ShaderVariableType::FVec2, 0),
+        ("color", ShaderVariableType::FVec4, 1),
+        ("transform", ShaderVariableType::FMat4x4, 2),
+    ]);
+
+    let result = reflection.validate_vertex_attributes(
+        &[
+            attribute(ShaderVariableType::FVec2, 0),
+            attribute(ShaderVariableType::UByte4Normalized, 1),
+        ],
+        &[
+            attribute(ShaderVariableType::FMat4x4, 2),
+            attribute(ShaderVariableType::Float, 6),
+        ],
+    );
+
+    assert_eq!(result, Ok(()));
+}
+
+#[test]
+fn missing_attribute_names_the_input() {
+    let reflection = reflection(&[
+        ("position", ShaderVariableType::FVec2, 0),
+        ("uv", ShaderVariableType::FVec2, 1),
+    ]);
+
+    let result = reflection
+        .validate_vertex_attributes(&[attribute(ShaderVariableType::FVec2, 0)], &[]);
+
+    assert_eq!(
+        result,
+        Err(ShaderValidationError::MissingVertexAttribute {
+            name: "uv".to_string(),
+            location: 1,
+            shader_type: ShaderVariableType::FVec2,
+        })
+    );
+}
+
+#[test]
+fn type_mismatch_names_both_types() {
+    let reflection = reflection(&[("color", ShaderVariableType::FVec4, 3)]);
+
+    let error = reflection
+        .validate_vertex_attributes(&[], &[attribute(ShaderVariableType::FVec3, 3)])
+        .unwrap_err();
+
+    assert_eq!(
+        error.to_string(),
+        "The shader input `color` at location 3 is FVec4, \
+         but the vertex buffer attribute is FVec3"
+    );
+}
+
+#[test]
+fn matrix_locations_overlap_following_attributes() {
+    let reflection = reflection(&[]);
+
+    let result = reflection.validate_vertex_attributes(
+        &[attribute(ShaderVariableType::FMat4x4, 0)],
+        &[attribute(ShaderVariableType::FVec4, 3)],
+    );
+
+    assert_eq!(
+        result,
+        Err(ShaderValidationError::DuplicateVertexAttribute { location: 3 })
+    );
+}
@@ -0,0 +1,61 @@
+use crate::core::streaming_instance_buffer::StreamingRing;
+
+#[test]
+fn slots_rotate_in_order() {
+    let mut ring = StreamingRing::new(3, 4);
+    assert_eq!(ring.current_slot(), 0);
+
+    let slots: Vec<usize> = (0..4)
+        .map(|_| {
+            ring.advance();
+            ring.current_slot()
+        })
+        .collect();
+    assert_eq!(slots, [1, 2, 0, 1]);
+}
+
+#[test]
+fn flush_uploads_only_new_instances() {
+    let mut ring = StreamingRing::new(2, 8);
+
+    let flush = ring.flush(3);
+    assert_eq!((flush.resized_capacity, flush.upload_range), (None, 0..3));
+    ring.mark_flushed(3);
+
+    let flush = ring.flush(5);
+    assert_eq!((flush.resized_capacity, flush.upload_range), (None, 3..5));
+    ring.mark_flushed(5);
+
+    assert!(ring.flush(5).upload_range.is_empty());
+}
+
+#[test]
+fn growth_past_capacity_reuploads_everything() {
+    let mut ring = StreamingRing::new(2, 4);
+    ring.flush(3);
+    ring.mark_flushed(3);
+
+    let flush = ring.flush(6);
+    assert_eq!((flush.resized_capacity, flush.upload_range), (Some(8), 0..6));
+    ring.mark_flushed(6);
+
+    let flush = ring.flush(20);
+    assert_eq!((flush.resized_capacity, flush.upload_range), (Some(20), 0..20));
+}
+
+#[test]
+fn slots_keep_their_capacity_after_wrap() {
+    let mut ring = StreamingRing::new(2, 4);
+    assert_eq!(ring.flush(6).resized_capacity, Some(8));
+    ring.mark_flushed(6);
+
+    // The other slot is still small
+    ring.advance();
+    assert_eq!(ring.flush(6).resized_capacity, Some(8));
+    ring.mark_flushed(6);
+
+    // Back at the first slot, its grown buffer is reused and nothing is flushed yet
+    ring.advance();
+    let flush = ring.flush(8);
+    assert_eq!((flush.resized_capacity, flush.upload_range), (None, 0..8));
+}
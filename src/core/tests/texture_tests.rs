@@ -0,0 +1,84 @@
+use crate::core::{
+    full_mip_level_count, texture::TextureLayout, TextureDescriptor, TextureFormat,
+    TextureMipmapFilter, TextureWrapMode,
+};
+
+#[test]
+fn full_mip_chain_goes_down_to_one_pixel() {
+    assert_eq!(full_mip_level_count(1, 1), 1);
+    assert_eq!(full_mip_level_count(0, 0), 1);
+    assert_eq!(full_mip_level_count(256, 256), 9);
+    assert_eq!(full_mip_level_count(1024, 600), 11);
+    assert_eq!(full_mip_level_count(3, 1025), 11);
+}
+
+#[test]
+fn descriptor_builders_set_sampling_options() {
+    let desc = TextureDescriptor::new(640, 480, TextureFormat::RGBA32UintSrgb)
+        .with_wrap_mode(TextureWrapMode::Repeat, TextureWrapMode::MirroredRepeat)
+        .with_mipmaps(TextureMipmapFilter::Linear);
+
+    assert_eq!(desc.wrap_mode_u, TextureWrapMode::Repeat);
+    assert_eq!(desc.wrap_mode_v, TextureWrapMode::MirroredRepeat);
+    assert_eq!(desc.mipmap_filter, TextureMipmapFilter::Linear);
+    assert_eq!(desc.mip_level_count, 10);
+
+    let desc = TextureDescriptor::new(640, 480, TextureFormat::R8Uint);
+    assert_eq!(desc.wrap_mode_u, TextureWrapMode::Default);
+    assert_eq!(desc.wrap_mode_v, TextureWrapMode::Default);
+    assert_eq!(desc.mipmap_filter, TextureMipmapFilter::None);
+    assert_eq!(desc.mip_level_count, 1);
+}
+
+#[test]
+fn format_sizes() {
+    assert_eq!(TextureFormat::RG16Uint.bytes_per_pixel(), 2);
+    assert_eq!(TextureFormat::RGBA64Float.bytes_per_pixel(), 8);
+    assert_eq!(TextureFormat::RGBA128Float.bytes_per_pixel(), 16);
+    assert_eq!(TextureFormat::RGBA128Float.channel_count(), 4);
+    assert!(TextureFormat::Depth24Stencil8.is_depth());
+    assert!(TextureFormat::BGRA32UintSrgb.is_srgb());
+    assert!(!TextureFormat::BGRA32Uint.is_srgb());
+}
+
+fn rgb_layout(mip_level_count: u32) -> TextureLayout {
+    TextureLayout {
+        texture_format: TextureFormat::RGB24Uint,
+        width: 16,
+        height: 8,
+        mip_level_count,
+    }
+}
+
+#[test]
+fn sub_data_fits_with_padded_rows_and_short_last_row() {
+    let layout = rgb_layout(1);
+
+    layout.assert_sub_data_fits(0, 0, 16, 8, 48, 48 * 8);
+    layout.assert_sub_data_fits(12, 4, 4, 4, 64, 64 * 3 + 12);
+    layout.assert_sub_data_fits(16, 8, 0, 0, 0, 0);
+}
+
+#[test]
+#[should_panic(expected = "row stride")]
+fn sub_data_stride_must_hold_a_row() {
+    rgb_layout(1).assert_sub_data_fits(0, 0, 4, 2, 8, 64);
+}
+
+#[test]
+#[should_panic(expected = "shorter than 2 rows")]
+fn sub_data_must_hold_all_rows() {
+    rgb_layout(1).assert_sub_data_fits(0, 0, 4, 2, 12, 12 + 11);
+}
+
+#[test]
+#[should_panic(expected = "doesn't fit")]
+fn sub_data_region_must_fit_in_texture() {
+    rgb_layout(1).assert_sub_data_fits(13, 0, 4, 1, 12, 12);
+}
+
+#[test]
+#[should_panic(expected = "mipmaps")]
+fn sub_data_rejects_mipmapped_textures() {
+    rgb_layout(5).assert_sub_data_fits(0, 0, 1, 1, 3, 3);
+}
@@ -0,0 +1,73 @@
+use division_math::{Matrix4x4, Vector2, Vector3, Vector4};
+
+use crate::core::{UniformBlock, UniformBlockLayout};
+
+#[repr(C)]
+#[derive(Clone, Copy, UniformBlock)]
+#[allow(dead_code)]
+struct Lighting {
+    view_projection: Matrix4x4,
+    #[uniform_block(name = "lightDirection")]
+    light_direction: Vector3,
+    intensity: f32,
+    ambient: Vector2,
+    _padding: [f32; 2],
+    colors: [Vector4; 2],
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, UniformBlock)]
+#[uniform_block(std430)]
+#[allow(dead_code)]
+struct Offsets {
+    scale: f32,
+    _padding: [f32; 3],
+    direction: Vector3,
+    _direction_padding: f32,
+    offsets: [Vector2; 3],
+}
+
+#[test]
+fn std140_block_declaration() {
+    assert_eq!(Lighting::LAYOUT, UniformBlockLayout::Std140);
+    assert_eq!(
+        Lighting::glsl_declaration("Lighting", 2),
+        "layout (std140, binding = 2) uniform Lighting\n{\n    \
+         mat4 view_projection;\n    \
+         vec3 lightDirection;\n    \
+         float intensity;\n    \
+         vec2 ambient;\n    \
+         vec4 colors[2];\n\
+         };\n"
+    );
+}
+
+#[test]
+fn msl_declaration_matches_block_offsets() {
+    assert_eq!(
+        Lighting::msl_declaration("Lighting"),
+        "struct Lighting\n{\n    \
+         float4x4 view_projection;\n    \
+         packed_float3 lightDirection;\n    \
+         float intensity;\n    \
+         float2 ambient;\n    \
+         float4 colors[2];\n\
+         };\n"
+    );
+}
+
+#[test]
+fn std430_arrays_are_tightly_packed() {
+    assert_eq!(Offsets::LAYOUT, UniformBlockLayout::Std430);
+    assert_eq!(
+        Offsets::GLSL_MEMBERS,
+        "    float scale;\n    vec3 direction;\n    vec2 offsets[3];\n"
+    );
+    assert_eq!(
+        Offsets::MSL_MEMBERS,
+        "    float scale;\n    \
+         char _padding_direction[12];\n    \
+         packed_float3 direction;\n    \
+         float2 offsets[3];\n"
+    );
+}
@@ -0,0 +1,69 @@
+use division_math::{Matrix4x4, Vector2, Vector4};
+
+use crate::core::{ShaderVariableType, VertexAttributeDescriptor, VertexData};
+
+#[repr(C, packed)]
+#[derive(Clone, Copy, VertexData)]
+#[allow(dead_code)]
+struct ExplicitLocations {
+    #[location(0)]
+    position: Vector2,
+    #[location(3)]
+    color: Vector4,
+}
+
+#[repr(C, packed)]
+#[derive(Clone, Copy, VertexData)]
+#[location(2)]
+#[allow(dead_code)]
+struct AutoLocations {
+    transform: Matrix4x4,
+    id: u32,
+    cell: [i32; 2],
+    #[normalized]
+    color: [u8; 4],
+    #[location(10)]
+    weights: [f32; 3],
+    #[normalized]
+    uv: [u16; 2],
+}
+
+fn locations_and_types<T: VertexData>() -> Vec<(i32, ShaderVariableType)> {
+    T::vertex_attributes()
+        .into_iter()
+        .map(|a| (a.location, a.field_type))
+        .collect()
+}
+
+#[test]
+fn explicit_locations_as_expected() {
+    assert_eq!(
+        locations_and_types::<ExplicitLocations>(),
+        [(0, ShaderVariableType::FVec2), (3, ShaderVariableType::FVec4)]
+    );
+}
+
+#[test]
+fn auto_locations_as_expected() {
+    assert_eq!(
+        locations_and_types::<AutoLocations>(),
+        [
+            (2, ShaderVariableType::FMat4x4),
+            (6, ShaderVariableType::UInteger),
+            (7, ShaderVariableType::IVec2),
+            (8, ShaderVariableType::UByte4Normalized),
+            (10, ShaderVariableType::FVec3),
+            (11, ShaderVariableType::UShort2Normalized),
+        ]
+    );
+}
+
+#[test]
+fn attribute_sizes_add_up_to_packed_struct_size() {
+    fn stride<T: VertexData>() -> usize {
+        T::vertex_attributes().iter().map(|a| a.field_type.size_of()).sum()
+    }
+
+    assert_eq!(stride::<ExplicitLocations>(), size_of::<ExplicitLocations>());
+    assert_eq!(stride::<AutoLocations>(), size_of::<AutoLocations>());
+}
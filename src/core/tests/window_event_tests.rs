@@ -0,0 +1,47 @@
+use division_math::Vector2;
+
+use crate::core::{
+    ffi::window::{DivisionWindowEvent, DivisionWindowEventType},
+    WindowEvent,
+};
+
+fn make_event(event_type: u32) -> DivisionWindowEvent {
+    DivisionWindowEvent {
+        event_type,
+        frame_buffer_width: 1600,
+        frame_buffer_height: 1200,
+        window_width: 800,
+        window_height: 600,
+        content_scale_x: 2.,
+        content_scale_y: 2.,
+    }
+}
+
+#[test]
+fn resize_carries_framebuffer_and_window_sizes() {
+    let event = make_event(DivisionWindowEventType::Resized as u32);
+
+    assert_eq!(
+        WindowEvent::from_division_event(&event),
+        Some(WindowEvent::Resized {
+            frame_buffer_size: Vector2::new(1600., 1200.),
+            window_size: Vector2::new(800., 600.),
+        })
+    );
+}
+
+#[test]
+fn close_request_is_its_own_event() {
+    let event = make_event(DivisionWindowEventType::CloseRequested as u32);
+
+    assert_eq!(
+        WindowEvent::from_division_event(&event),
+        Some(WindowEvent::CloseRequested)
+    );
+}
+
+#[test]
+fn unknown_event_types_are_skipped() {
+    assert_eq!(WindowEvent::from_division_event(&make_event(0)), None);
+    assert_eq!(WindowEvent::from_division_event(&make_event(100)), None);
+}
@@ -1,9 +1,11 @@
 use std::ffi::c_void;
 
+use division_math::Vector4;
+
 use super::{
     ffi::texture::{
         division_engine_texture_alloc, division_engine_texture_free,
-        division_engine_texture_set_data,
+        division_engine_texture_set_data, division_engine_texture_set_sub_data,
     },
     context::Error,
     Context, DivisionId, Image,
@@ -15,8 +17,19 @@ pub use super::ffi::texture::{
     DivisionTextureDescriptor as TextureDescriptor,
     DivisionTextureFormat as TextureFormat,
     DivisionTextureMinMagFilter as TextureMinMagFilter,
+    DivisionTextureMipmapFilter as TextureMipmapFilter,
+    DivisionTextureWrapMode as TextureWrapMode,
 };
 
+/// Format and size of a created texture, that uploads are validated against
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct TextureLayout {
+    pub texture_format: TextureFormat,
+    pub width: u32,
+    pub height: u32,
+    pub mip_level_count: u32,
+}
+
 impl Context {
     pub fn create_texture_buffer(
         &mut self,
@@ -33,6 +46,16 @@ impl Context {
             }
         }
 
+        self.runner_state_mut().texture_layouts.insert(
+            texture_id,
+            TextureLayout {
+                texture_format: texture_descriptor.texture_format,
+                width: texture_descriptor.width,
+                height: texture_descriptor.height,
+                mip_level_count: texture_descriptor.mip_level_count,
+            },
+        );
+
         Ok(texture_id)
     }
 
@@ -46,6 +69,7 @@ impl Context {
         Ok(id)
     }
 
+    /// Replaces the first mip level, the rest are generated from it
     pub fn set_texture_buffer_data(&mut self, texture_id: DivisionId, data: &[u8]) {
         unsafe {
             division_engine_texture_set_data(
@@ -70,6 +94,46 @@ impl Context {
         }
     }
 
+    /// Uploads the `width` x `height` region at `x`, `y` of the texture.
+    /// `row_stride` is the distance in bytes between the starts of rows in `data`.
+    /// Other mip levels aren't regenerated from a region, so mipmapped textures
+    /// must be updated with `set_texture_buffer_data`
+    ///
+    /// # Panics
+    /// If the texture has mipmaps, the region doesn't fit in the texture,
+    /// or `data` doesn't hold `height` rows of `width` pixels with the `row_stride`
+    #[allow(clippy::too_many_arguments)]
+    pub fn set_texture_buffer_sub_data(
+        &mut self,
+        texture_id: DivisionId,
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32,
+        row_stride: usize,
+        data: &[u8],
+    ) {
+        let layout = *self
+            .runner_state()
+            .texture_layouts
+            .get(&texture_id)
+            .unwrap_or_else(|| panic!("Unknown texture {texture_id}"));
+        layout.assert_sub_data_fits(x, y, width, height, row_stride, data.len());
+
+        unsafe {
+            division_engine_texture_set_sub_data(
+                &mut *self,
+                texture_id,
+                x,
+                y,
+                width,
+                height,
+                row_stride as u32,
+                data.as_ptr() as *const c_void,
+            )
+        }
+    }
+
     pub fn create_texture_buffer_from_image(
         &mut self,
         image: &Image,
@@ -89,22 +153,20 @@ impl Context {
         min_filter: TextureMinMagFilter,
         mag_filter: TextureMinMagFilter,
     ) -> Result<DivisionId, Error> {
-        let mut desc = TextureDescriptor::new(
-            image.width(),
-            image.height(),
-            channels_to_texture_format(image.channels())?,
-        )
-        .with_min_mag_filter(min_filter, mag_filter);
+        let mut desc = TextureDescriptor::from_image(image)?
+            .with_min_mag_filter(min_filter, mag_filter);
 
-        if let Some(s) = channels_swizzle {
-            desc.channels_swizzle = s;
-            desc.has_channels_swizzle = true;
-        }
+        desc.has_channels_swizzle = channels_swizzle.is_some();
+        desc.channels_swizzle = channels_swizzle.unwrap_or_default();
 
         self.create_texture_buffer_from_data(&desc, image.data())
     }
 
     pub fn delete_texture_buffer(&mut self, texture_buffer_id: DivisionId) {
+        self.runner_state_mut()
+            .texture_layouts
+            .remove(&texture_buffer_id);
+
         unsafe {
             division_engine_texture_free(&mut *self, texture_buffer_id);
         }
@@ -115,6 +177,7 @@ impl Context {
 fn channels_to_texture_format(channels: usize) -> Result<TextureFormat, Error> {
     Ok(match channels {
         1 => TextureFormat::R8Uint,
+        2 => TextureFormat::RG16Uint,
         3 => TextureFormat::RGB24Uint,
         4 => TextureFormat::RGBA32Uint,
         c => {
@@ -126,7 +189,9 @@ fn channels_to_texture_format(channels: usize) -> Result<TextureFormat, Error> {
 }
 
 #[inline]
-fn channels_to_default_swizzle(channels: usize) -> Option<TextureChannelsSwizzle> {
+pub(crate) fn channels_to_default_swizzle(
+    channels: usize,
+) -> Option<TextureChannelsSwizzle> {
     match channels {
         1 => Some(TextureChannelsSwizzle::all(TextureChannelSwizzleVariant::Red)),
         3 => Some(TextureChannelsSwizzle {
@@ -171,9 +236,29 @@ impl TextureDescriptor {
             channels_swizzle: TextureChannelsSwizzle::default(),
             min_filter: TextureMinMagFilter::Nearest,
             mag_filter: TextureMinMagFilter::Nearest,
+            mipmap_filter: TextureMipmapFilter::None,
+            wrap_mode_u: TextureWrapMode::Default,
+            wrap_mode_v: TextureWrapMode::Default,
+            border_color: [0., 0., 0., 0.],
+            mip_level_count: 1,
         }
     }
 
+    /// Descriptor with the image size and format, and the swizzle
+    /// that expands grayscale and RGB images to RGBA
+    pub fn from_image(image: &Image) -> Result<Self, Error> {
+        let desc = TextureDescriptor::new(
+            image.width(),
+            image.height(),
+            channels_to_texture_format(image.channels())?,
+        );
+
+        Ok(match channels_to_default_swizzle(image.channels()) {
+            Some(swizzle) => desc.with_channels_swizzle(swizzle),
+            None => desc,
+        })
+    }
+
     pub fn with_channels_swizzle(
         mut self,
         channels_swizzle: TextureChannelsSwizzle,
@@ -194,4 +279,132 @@ impl TextureDescriptor {
 
         self
     }
+
+    pub fn with_wrap_mode(
+        mut self,
+        wrap_mode_u: TextureWrapMode,
+        wrap_mode_v: TextureWrapMode,
+    ) -> Self {
+        self.wrap_mode_u = wrap_mode_u;
+        self.wrap_mode_v = wrap_mode_v;
+
+        self
+    }
+
+    /// Color sampled outside of the texture with `TextureWrapMode::ClampToBorder`
+    pub fn with_border_color(mut self, color: Vector4) -> Self {
+        self.border_color = [color.r(), color.g(), color.b(), color.a()];
+
+        self
+    }
+
+    /// Allocates the full mip chain, generated on every data upload
+    pub fn with_mipmaps(mut self, mipmap_filter: TextureMipmapFilter) -> Self {
+        self.mipmap_filter = mipmap_filter;
+        self.mip_level_count = full_mip_level_count(self.width, self.height);
+
+        self
+    }
+}
+
+impl TextureLayout {
+    pub(crate) fn assert_sub_data_fits(
+        &self,
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32,
+        row_stride: usize,
+        data_len: usize,
+    ) {
+        assert!(
+            self.mip_level_count <= 1,
+            "Regions of textures with mipmaps can't be uploaded"
+        );
+        assert!(
+            x as u64 + width as u64 <= self.width as u64
+                && y as u64 + height as u64 <= self.height as u64,
+            "The region {width}x{height} at ({x}, {y}) doesn't fit in the texture {}x{}",
+            self.width,
+            self.height
+        );
+
+        let row_len = width as usize * self.texture_format.bytes_per_pixel();
+        assert!(
+            row_stride >= row_len,
+            "The row stride {row_stride} is shorter than a row of {row_len} bytes"
+        );
+        assert!(
+            height == 0 || data_len >= row_stride * (height as usize - 1) + row_len,
+            "The data is shorter than {height} rows of {row_len} bytes \
+             with the stride {row_stride}"
+        );
+    }
+}
+
+impl TextureFormat {
+    /// Channels of the format, depth and stencil are counted as one
+    pub fn channel_count(&self) -> usize {
+        match self {
+            TextureFormat::R8Uint
+            | TextureFormat::R16Float
+            | TextureFormat::Depth32Float
+            | TextureFormat::Depth24Stencil8 => 1,
+            TextureFormat::RG16Uint => 2,
+            TextureFormat::RGB24Uint => 3,
+            TextureFormat::RGBA32Uint
+            | TextureFormat::BGRA32Uint
+            | TextureFormat::RGBA32UintSrgb
+            | TextureFormat::BGRA32UintSrgb
+            | TextureFormat::RGBA64Float
+            | TextureFormat::RGBA128Float => 4,
+        }
+    }
+
+    pub fn bytes_per_pixel(&self) -> usize {
+        match self {
+            TextureFormat::R8Uint => 1,
+            TextureFormat::RG16Uint | TextureFormat::R16Float => 2,
+            TextureFormat::RGB24Uint => 3,
+            TextureFormat::RGBA32Uint
+            | TextureFormat::BGRA32Uint
+            | TextureFormat::RGBA32UintSrgb
+            | TextureFormat::BGRA32UintSrgb
+            | TextureFormat::Depth32Float
+            | TextureFormat::Depth24Stencil8 => 4,
+            TextureFormat::RGBA64Float => 8,
+            TextureFormat::RGBA128Float => 16,
+        }
+    }
+
+    /// Channels of an image with 8 bits per channel in RGBA order, as `Image`
+    /// stores them, or `None` if the format doesn't have that layout
+    pub fn image_channel_count(&self) -> Option<usize> {
+        match self {
+            TextureFormat::R8Uint => Some(1),
+            TextureFormat::RG16Uint => Some(2),
+            TextureFormat::RGB24Uint => Some(3),
+            TextureFormat::RGBA32Uint | TextureFormat::RGBA32UintSrgb => Some(4),
+            _ => None,
+        }
+    }
+
+    pub fn is_depth(&self) -> bool {
+        matches!(
+            self,
+            TextureFormat::Depth32Float | TextureFormat::Depth24Stencil8
+        )
+    }
+
+    pub fn is_srgb(&self) -> bool {
+        matches!(
+            self,
+            TextureFormat::RGBA32UintSrgb | TextureFormat::BGRA32UintSrgb
+        )
+    }
+}
+
+/// Mip levels down to 1x1 for the size
+pub fn full_mip_level_count(width: u32, height: u32) -> u32 {
+    u32::BITS - width.max(height).max(1).leading_zeros()
 }
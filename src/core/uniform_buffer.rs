@@ -14,6 +14,38 @@ use super::{
     Context, DivisionId, context::Error,
 };
 
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum UniformBlockLayout {
+    Std140,
+    Std430,
+}
+
+/// Data of an uniform buffer whose Rust layout is checked against the shader
+/// block layout. Implemented with `#[derive(UniformBlock)]`
+pub trait UniformBlock: Copy {
+    const LAYOUT: UniformBlockLayout;
+    /// GLSL member declarations, one per line
+    const GLSL_MEMBERS: &'static str;
+    /// MSL struct members with the explicit padding MSL needs to match the layout
+    const MSL_MEMBERS: &'static str;
+
+    fn glsl_declaration(block_name: &str, binding: u32) -> String {
+        let layout = match Self::LAYOUT {
+            UniformBlockLayout::Std140 => "std140",
+            UniformBlockLayout::Std430 => "std430",
+        };
+
+        format!(
+            "layout ({layout}, binding = {binding}) uniform {block_name}\n{{\n{}}};\n",
+            Self::GLSL_MEMBERS
+        )
+    }
+
+    fn msl_declaration(struct_name: &str) -> String {
+        format!("struct {struct_name}\n{{\n{}}};\n", Self::MSL_MEMBERS)
+    }
+}
+
 pub struct UniformBufferData<'a, T> {
     pub data: &'a mut T,
 
@@ -49,6 +81,12 @@ impl Context {
         self.create_uniform_buffer(std::mem::size_of::<T>())
     }
 
+    pub fn create_uniform_block_buffer<T: UniformBlock>(
+        &mut self,
+    ) -> Result<DivisionId, Error> {
+        self.create_uniform_buffer(std::mem::size_of::<T>())
+    }
+
     pub fn uniform_buffer_data<T>(
         &mut self,
         uniform_buffer_id: DivisionId,
@@ -1,4 +1,7 @@
-use std::mem::MaybeUninit;
+use std::{
+    mem::MaybeUninit,
+    ops::{Deref, DerefMut, Range},
+};
 
 use super::{
     context::Error,
@@ -7,8 +10,13 @@ use super::{
         vertex_buffer::{
             division_engine_vertex_buffer_alloc,
             division_engine_vertex_buffer_borrow_data,
-            division_engine_vertex_buffer_free, division_engine_vertex_buffer_resize,
-            division_engine_vertex_buffer_return_data, DivisionVertexBufferBorrowedData,
+            division_engine_vertex_buffer_borrow_instance_range,
+            division_engine_vertex_buffer_free,
+            division_engine_vertex_buffer_get_descriptor,
+            division_engine_vertex_buffer_resize,
+            division_engine_vertex_buffer_return_data,
+            division_engine_vertex_buffer_return_instance_range,
+            DivisionVertexBufferBorrowedData, DivisionVertexBufferBorrowedRange,
             DivisionVertexBufferDescriptor,
         },
     },
@@ -30,6 +38,31 @@ pub trait VertexData {
     fn vertex_attributes() -> Vec<VertexAttributeDescriptor>;
 }
 
+impl ShaderVariableType {
+    /// Size of the attribute in a vertex buffer, in bytes
+    pub fn size_of(&self) -> usize {
+        match self {
+            ShaderVariableType::Float
+            | ShaderVariableType::Integer
+            | ShaderVariableType::UInteger
+            | ShaderVariableType::UByte4Normalized
+            | ShaderVariableType::UShort2Normalized => 4,
+            ShaderVariableType::Double
+            | ShaderVariableType::FVec2
+            | ShaderVariableType::IVec2
+            | ShaderVariableType::UVec2
+            | ShaderVariableType::UShort4Normalized => 8,
+            ShaderVariableType::FVec3
+            | ShaderVariableType::IVec3
+            | ShaderVariableType::UVec3 => 12,
+            ShaderVariableType::FVec4
+            | ShaderVariableType::IVec4
+            | ShaderVariableType::UVec4 => 16,
+            ShaderVariableType::FMat4x4 => 64,
+        }
+    }
+}
+
 pub struct VertexBufferData<'a, TVertexData, TInstanceData> {
     pub per_vertex_data: &'a mut [TVertexData],
     pub per_instance_data: &'a mut [TInstanceData],
@@ -40,6 +73,17 @@ pub struct VertexBufferData<'a, TVertexData, TInstanceData> {
     vertex_buffer_id: u32,
 }
 
+/// Borrowed instances of a vertex buffer. Only the ranges marked with `mark_dirty`
+/// are uploaded when it's dropped
+pub struct VertexBufferInstanceRange<'a, TInstanceData> {
+    data: &'a mut [TInstanceData],
+    dirty_range: Option<Range<usize>>,
+
+    ctx: *mut DivisionContext,
+    borrowed: DivisionVertexBufferBorrowedRange,
+    vertex_buffer_id: u32,
+}
+
 impl Context {
     pub fn create_vertex_buffer<TVertexData: VertexData, TInstanceData: VertexData>(
         &mut self,
@@ -54,6 +98,35 @@ impl Context {
         )
     }
 
+    /// Creates a vertex buffer sized to fit the data and uploads it
+    pub fn create_vertex_buffer_with_data<TVertexData, TInstanceData>(
+        &mut self,
+        per_vertex_data: &[TVertexData],
+        vertex_indices: &[u32],
+        per_instance_data: &[TInstanceData],
+        topology: RenderTopology,
+    ) -> Result<DivisionId, Error>
+    where
+        TVertexData: VertexData + Copy,
+        TInstanceData: VertexData + Copy,
+    {
+        let id = self.create_vertex_buffer::<TVertexData, TInstanceData>(
+            VertexBufferSize {
+                vertex_count: per_vertex_data.len() as u32,
+                index_count: vertex_indices.len() as u32,
+                instance_count: per_instance_data.len() as u32,
+            },
+            topology,
+        )?;
+
+        let data = self.vertex_buffer_data::<TVertexData, TInstanceData>(id);
+        data.per_vertex_data.copy_from_slice(per_vertex_data);
+        data.vertex_indices.copy_from_slice(vertex_indices);
+        data.per_instance_data.copy_from_slice(per_instance_data);
+
+        Ok(id)
+    }
+
     pub fn create_vertex_buffer_with_attributes(
         &mut self,
         per_vertex_attributes: &[VertexAttributeDescriptor],
@@ -85,6 +158,37 @@ impl Context {
         Ok(id)
     }
 
+    /// Returns per-vertex and per-instance attributes the buffer was created with
+    pub fn vertex_buffer_attributes(
+        &mut self,
+        vertex_buffer_id: DivisionId,
+    ) -> Result<(Vec<VertexAttributeDescriptor>, Vec<VertexAttributeDescriptor>), Error> {
+        unsafe {
+            let mut descriptor = MaybeUninit::uninit();
+            if !division_engine_vertex_buffer_get_descriptor(
+                self,
+                vertex_buffer_id,
+                descriptor.as_mut_ptr(),
+            ) {
+                return Err(Error::Core(format!(
+                    "Vertex buffer with id `{vertex_buffer_id}` doesn't exist"
+                )));
+            }
+
+            let descriptor: DivisionVertexBufferDescriptor = descriptor.assume_init();
+            Ok((
+                copy_attributes(
+                    descriptor.per_vertex_attributes,
+                    descriptor.per_vertex_attribute_count,
+                ),
+                copy_attributes(
+                    descriptor.per_instance_attributes,
+                    descriptor.per_instance_attribute_count,
+                ),
+            ))
+        }
+    }
+
     pub fn vertex_buffer_resize(
         &mut self,
         vertex_buffer_id: DivisionId,
@@ -133,6 +237,52 @@ impl Context {
         }
     }
 
+    /// Borrows only the instances in `range`, unlike `vertex_buffer_data`
+    /// which borrows and uploads the whole buffer.
+    /// Fails if `TInstance` isn't the size of the buffer's instance
+    pub fn vertex_buffer_instance_range<'a, TInstance: VertexData>(
+        &'a mut self,
+        vertex_buffer_id: DivisionId,
+        range: Range<usize>,
+    ) -> Result<VertexBufferInstanceRange<'a, TInstance>, Error> {
+        let (_, per_instance) = self.vertex_buffer_attributes(vertex_buffer_id)?;
+        let stride: usize = per_instance.iter().map(|a| a.field_type.size_of()).sum();
+        if stride != std::mem::size_of::<TInstance>() {
+            return Err(Error::Core(format!(
+                "Instance stride of the vertex buffer is {stride} bytes, \
+                 but the instance type is {} bytes",
+                std::mem::size_of::<TInstance>()
+            )));
+        }
+
+        unsafe {
+            let mut borrowed = MaybeUninit::uninit();
+            if !division_engine_vertex_buffer_borrow_instance_range(
+                self,
+                vertex_buffer_id,
+                range.start as u32,
+                range.len() as u32,
+                borrowed.as_mut_ptr(),
+            ) {
+                return Err(Error::Core(format!(
+                    "Failed to borrow instances {range:?} of the vertex buffer"
+                )));
+            }
+
+            let borrowed = borrowed.assume_init();
+            Ok(VertexBufferInstanceRange {
+                data: std::slice::from_raw_parts_mut(
+                    borrowed.data_ptr as *mut TInstance,
+                    borrowed.count as usize,
+                ),
+                dirty_range: None,
+                ctx: &mut *self,
+                borrowed,
+                vertex_buffer_id,
+            })
+        }
+    }
+
     pub fn delete_vertex_buffer(&mut self, vertex_buffer_id: DivisionId) {
         unsafe {
             division_engine_vertex_buffer_free(&mut *self, vertex_buffer_id);
@@ -151,3 +301,72 @@ impl<'a, TX, TY> Drop for VertexBufferData<'a, TX, TY> {
         }
     }
 }
+
+impl<'a, T> VertexBufferInstanceRange<'a, T> {
+    /// Marks the instances to upload, relative to the borrowed range.
+    /// Several calls upload the span that covers all of them
+    pub fn mark_dirty(&mut self, range: Range<usize>) {
+        assert!(range.end <= self.data.len(), "The dirty range is out of bounds");
+
+        self.dirty_range = Some(match self.dirty_range.take() {
+            Some(r) => r.start.min(range.start)..r.end.max(range.end),
+            None => range,
+        });
+    }
+
+    pub fn mark_all_dirty(&mut self) {
+        self.mark_dirty(0..self.data.len());
+    }
+
+    /// Copies the instances at `offset` and marks them dirty
+    pub fn write(&mut self, offset: usize, instances: &[T])
+    where
+        T: Copy,
+    {
+        let range = offset..offset + instances.len();
+        self.data[range.clone()].copy_from_slice(instances);
+        self.mark_dirty(range);
+    }
+}
+
+impl<'a, T> Deref for VertexBufferInstanceRange<'a, T> {
+    type Target = [T];
+
+    fn deref(&self) -> &Self::Target {
+        self.data
+    }
+}
+
+/// Mutable access marks the whole range dirty, use `write` to upload only a part
+impl<'a, T> DerefMut for VertexBufferInstanceRange<'a, T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.mark_all_dirty();
+        self.data
+    }
+}
+
+impl<'a, T> Drop for VertexBufferInstanceRange<'a, T> {
+    fn drop(&mut self) {
+        let dirty_range = self.dirty_range.clone().unwrap_or(0..0);
+        unsafe {
+            division_engine_vertex_buffer_return_instance_range(
+                self.ctx,
+                self.vertex_buffer_id,
+                &self.borrowed,
+                dirty_range.start as u32,
+                dirty_range.len() as u32,
+            )
+        }
+    }
+}
+
+unsafe fn copy_attributes(
+    attributes: *const VertexAttributeDescriptor,
+    count: i32,
+) -> Vec<VertexAttributeDescriptor> {
+    if attributes.is_null() || count <= 0 {
+        return Vec::new();
+    }
+
+    std::slice::from_raw_parts(attributes, count as usize).to_vec()
+}
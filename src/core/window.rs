@@ -0,0 +1,53 @@
+use division_math::Vector2;
+
+use super::ffi::window::{DivisionWindowEvent, DivisionWindowEventType};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum WindowEvent {
+    Resized {
+        frame_buffer_size: Vector2,
+        window_size: Vector2,
+    },
+    FocusGained,
+    FocusLost,
+    Minimized,
+    Restored,
+    ContentScaleChanged {
+        scale: Vector2,
+    },
+    /// Also delivered to `LifecycleManager::close_requested`, that can keep
+    /// the window open
+    CloseRequested,
+}
+
+impl WindowEvent {
+    /// Returns `None` for event types the bindings don't know
+    pub(crate) fn from_division_event(
+        event: &DivisionWindowEvent,
+    ) -> Option<WindowEvent> {
+        let event_type = DivisionWindowEventType::try_from(event.event_type).ok()?;
+
+        Some(match event_type {
+            DivisionWindowEventType::Resized => WindowEvent::Resized {
+                frame_buffer_size: Vector2::new(
+                    event.frame_buffer_width as f32,
+                    event.frame_buffer_height as f32,
+                ),
+                window_size: Vector2::new(
+                    event.window_width as f32,
+                    event.window_height as f32,
+                ),
+            },
+            DivisionWindowEventType::FocusGained => WindowEvent::FocusGained,
+            DivisionWindowEventType::FocusLost => WindowEvent::FocusLost,
+            DivisionWindowEventType::Minimized => WindowEvent::Minimized,
+            DivisionWindowEventType::Restored => WindowEvent::Restored,
+            DivisionWindowEventType::ContentScaleChanged => {
+                WindowEvent::ContentScaleChanged {
+                    scale: Vector2::new(event.content_scale_x, event.content_scale_y),
+                }
+            }
+            DivisionWindowEventType::CloseRequested => WindowEvent::CloseRequested,
+        })
+    }
+}
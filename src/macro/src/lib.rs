@@ -1,87 +1,555 @@
-use std::str::FromStr;
-
 use proc_macro::TokenStream;
-use syn::{parse_macro_input, Data, DeriveInput};
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::{quote, quote_spanned};
+use syn::{
+    parse_macro_input, spanned::Spanned, Attribute, Data, DeriveInput, Error, Expr,
+    Fields, Ident, Lit, LitInt, Type,
+};
 
-#[proc_macro_derive(VertexData, attributes(location))]
+/// Implements `VertexData` for a struct, one vertex attribute per field.
+///
+/// Field locations are set with `#[location(n)]`. Fields without it take the
+/// location that follows the previous field, so `#[location(n)]` on the struct
+/// itself sets the location of the first field.
+/// Integer arrays of `u8` and `u16` must be marked `#[normalized]`, they are
+/// read by shaders as floats in `[0, 1]`.
+///
+/// The struct must be `repr(C)` or `repr(packed)`, the fields are checked at
+/// compile time to be tightly packed in the declaration order
+#[proc_macro_derive(VertexData, attributes(location, normalized))]
 pub fn derive_vertex_data(item: TokenStream) -> TokenStream {
     let input = parse_macro_input!(item as DeriveInput);
-    let locations = match input.data {
-        Data::Struct(s) => gather_locations(s.fields),
-        Data::Enum(_) | Data::Union(_) => unimplemented!(),
+
+    match expand_vertex_data(&input) {
+        Ok(tokens) => tokens.into(),
+        Err(e) => e.to_compile_error().into(),
+    }
+}
+
+#[proc_macro_attribute]
+pub fn location(_attr: TokenStream, item: TokenStream) -> TokenStream {
+    item
+}
+
+struct VertexAttribute {
+    field_ident: Ident,
+    field_type: Type,
+    shader_type: ShaderType,
+    location: u32,
+    span: Span,
+}
+
+#[derive(Clone, Copy)]
+struct ShaderType {
+    variant: &'static str,
+    size: usize,
+    location_count: u32,
+}
+
+fn expand_vertex_data(input: &DeriveInput) -> Result<TokenStream2, Error> {
+    let fields = match &input.data {
+        Data::Struct(s) => match &s.fields {
+            Fields::Named(f) => &f.named,
+            _ => {
+                return Err(Error::new(
+                    input.ident.span(),
+                    "VertexData can only be derived for structs with named fields",
+                ))
+            }
+        },
+        _ => {
+            return Err(Error::new(
+                input.ident.span(),
+                "VertexData can only be derived for structs",
+            ))
+        }
     };
 
-    let attrs_list = locations
-        .into_iter()
-        .map(|(index, ty_name)| (index, map_typename_to_shader_type(ty_name)))
-        .map(|(index, var_ty)| {
-            format!(
-                "VertexAttributeDescriptor {{ 
-                    location: {index}, 
-                    field_type: ShaderVariableType::{var_ty} 
-                }}"
-            )
-        })
-        .collect::<Vec<String>>()
-        .join(",");
-    
-    let data_ty_name = input.ident.to_string();
-    let vertex_attr_impl = format!(
-        "impl VertexData for {data_ty_name} {{
-            fn vertex_attributes() -> Vec<VertexAttributeDescriptor> {{
-                [{attrs_list}]
-                    .into_iter()
-                    .collect::<Vec<VertexAttributeDescriptor>>()
-            }}
-        }}"
-    );
+    if !input.generics.params.is_empty() {
+        return Err(Error::new(
+            input.generics.span(),
+            "VertexData can't be derived for generic structs",
+        ));
+    }
+
+    check_repr(input)?;
+
+    let mut next_location = parse_location(&input.attrs)?.unwrap_or(0);
+    let mut attributes: Vec<VertexAttribute> = Vec::new();
+    for field in fields {
+        let field_ident = field.ident.clone().unwrap();
+        let is_normalized = field.attrs.iter().any(|a| a.path().is_ident("normalized"));
+        let shader_type = map_type_to_shader_type(&field.ty, is_normalized)?;
+        let location = parse_location(&field.attrs)?.unwrap_or(next_location);
+
+        if let Some(other) = attributes.iter().find(|a| {
+            location < a.location + a.shader_type.location_count
+                && a.location < location + shader_type.location_count
+        }) {
+            return Err(Error::new(
+                field.span(),
+                format!(
+                    "Location {location} of `{field_ident}` overlaps with \
+                     the location {} of `{}`",
+                    other.location, other.field_ident
+                ),
+            ));
+        }
+
+        next_location = location + shader_type.location_count;
+        attributes.push(VertexAttribute {
+            field_ident,
+            field_type: field.ty.clone(),
+            shader_type,
+            location,
+            span: field.span(),
+        });
+    }
+
+    let struct_ident = &input.ident;
+    let descriptors = attributes.iter().map(|a| {
+        let location = a.location as i32;
+        let variant = Ident::new(a.shader_type.variant, a.span);
+        quote_spanned! {a.span=>
+            VertexAttributeDescriptor {
+                location: #location,
+                field_type: ShaderVariableType::#variant,
+            }
+        }
+    });
+
+    let mut offset = quote! { 0usize };
+    let mut layout_checks = Vec::new();
+    for a in &attributes {
+        let field_ident = &a.field_ident;
+        let field_type = &a.field_type;
+        let size = a.shader_type.size;
+        let message = format!(
+            "`{field_ident}` must take {size} bytes right after the previous field"
+        );
+        layout_checks.push(quote_spanned! {a.span=>
+            assert!(
+                ::core::mem::size_of::<#field_type>() == #size
+                    && ::core::mem::offset_of!(#struct_ident, #field_ident) == #offset,
+                #message
+            );
+        });
+        offset = quote! { #offset + #size };
+    }
 
-    TokenStream::from_str(&vertex_attr_impl).unwrap()
+    let size_message = format!("`{struct_ident}` must not have padding");
+    Ok(quote! {
+        impl VertexData for #struct_ident {
+            fn vertex_attributes() -> Vec<VertexAttributeDescriptor> {
+                vec![#(#descriptors),*]
+            }
+        }
+
+        const _: () = {
+            #(#layout_checks)*
+            assert!(::core::mem::size_of::<#struct_ident>() == #offset, #size_message);
+        };
+    })
+}
+
+fn check_repr(input: &DeriveInput) -> Result<(), Error> {
+    let mut has_layout = false;
+    for attr in input.attrs.iter().filter(|a| a.path().is_ident("repr")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("C") || meta.path.is_ident("packed") {
+                has_layout = true;
+            }
+            if meta.input.peek(syn::token::Paren) {
+                let _content;
+                syn::parenthesized!(_content in meta.input);
+            }
+            Ok(())
+        })?;
+    }
+
+    match has_layout {
+        true => Ok(()),
+        false => Err(Error::new(
+            input.ident.span(),
+            "VertexData requires `#[repr(C)]` or `#[repr(packed)]` \
+             to have a predictable field layout",
+        )),
+    }
+}
+
+fn parse_location(attrs: &[Attribute]) -> Result<Option<u32>, Error> {
+    let mut location = None;
+    for attr in attrs.iter().filter(|a| a.path().is_ident("location")) {
+        if location.is_some() {
+            return Err(Error::new(attr.span(), "Duplicate `location` attribute"));
+        }
+        location = Some(attr.parse_args::<LitInt>()?.base10_parse::<u32>()?);
+    }
+
+    Ok(location)
 }
 
-fn map_typename_to_shader_type(type_name: String) -> String {
-    String::from(match type_name.as_str() {
-        "i32" => "Integer",
-        "f32" => "Float",
-        "f64" => "Double",
-        "Vector2" => "FVec2",
-        "Vector3" => "FVec3",
-        "Vector4" => "FVec4",
-        "Matrix4x4" => "FMat4x4",
-        _ => panic!("Unknown shader variable type"),
+fn map_type_to_shader_type(ty: &Type, is_normalized: bool) -> Result<ShaderType, Error> {
+    let unknown_type = || {
+        Error::new(
+            ty.span(),
+            "Unsupported vertex attribute type. Expected one of f32, f64, i32, u32, \
+             Vector2, Vector3, Vector4, Matrix4x4, [f32; 1..=4], [i32; 2..=4], \
+             [u32; 2..=4], #[normalized] [u8; 4], #[normalized] [u16; 2 | 4]",
+        )
+    };
+    let shader_type = |variant, size, location_count| ShaderType {
+        variant,
+        size,
+        location_count,
+    };
+
+    let (element, len) = match ty {
+        Type::Path(p) => {
+            let segment = p.path.segments.last().ok_or_else(unknown_type)?;
+            (segment.ident.to_string(), None)
+        }
+        Type::Array(a) => {
+            let element = match &*a.elem {
+                Type::Path(p) => p.path.get_ident().ok_or_else(unknown_type)?.to_string(),
+                _ => return Err(unknown_type()),
+            };
+            let len = match &a.len {
+                Expr::Lit(l) => match &l.lit {
+                    Lit::Int(i) => i.base10_parse::<usize>()?,
+                    _ => return Err(unknown_type()),
+                },
+                _ => {
+                    return Err(Error::new(
+                        a.len.span(),
+                        "The array length must be an integer literal",
+                    ))
+                }
+            };
+            (element, Some(len))
+        }
+        _ => return Err(unknown_type()),
+    };
+
+    let is_normalized_type = matches!(element.as_str(), "u8" | "u16");
+    if is_normalized != is_normalized_type {
+        return Err(Error::new(
+            ty.span(),
+            match is_normalized {
+                true => "Only u8 and u16 arrays can be normalized",
+                false => "u8 and u16 arrays must be marked #[normalized]",
+            },
+        ));
+    }
+
+    Ok(match (element.as_str(), len) {
+        ("f32", None | Some(1)) => shader_type("Float", 4, 1),
+        ("f64", None) => shader_type("Double", 8, 1),
+        ("i32", None) => shader_type("Integer", 4, 1),
+        ("u32", None) => shader_type("UInteger", 4, 1),
+        ("Vector2", None) | ("f32", Some(2)) => shader_type("FVec2", 8, 1),
+        ("Vector3", None) | ("f32", Some(3)) => shader_type("FVec3", 12, 1),
+        ("Vector4", None) | ("f32", Some(4)) => shader_type("FVec4", 16, 1),
+        ("Matrix4x4", None) => shader_type("FMat4x4", 64, 4),
+        ("i32", Some(2)) => shader_type("IVec2", 8, 1),
+        ("i32", Some(3)) => shader_type("IVec3", 12, 1),
+        ("i32", Some(4)) => shader_type("IVec4", 16, 1),
+        ("u32", Some(2)) => shader_type("UVec2", 8, 1),
+        ("u32", Some(3)) => shader_type("UVec3", 12, 1),
+        ("u32", Some(4)) => shader_type("UVec4", 16, 1),
+        ("u8", Some(4)) => shader_type("UByte4Normalized", 4, 1),
+        ("u16", Some(2)) => shader_type("UShort2Normalized", 4, 1),
+        ("u16", Some(4)) => shader_type("UShort4Normalized", 8, 1),
+        _ => return Err(unknown_type()),
     })
 }
 
-fn gather_locations(fields: syn::Fields) -> Vec<(u32, String)> {
-    let mut locations = Vec::new();
+/// Implements `UniformBlock` for a `repr(C)` struct and checks at compile time
+/// that every field is where the std140 (or std430 with `#[uniform_block(std430)]`)
+/// layout puts it.
+///
+/// Fields starting with `_` are Rust only padding and aren't declared in shaders.
+/// A shader member can be renamed with `#[uniform_block(name = "...")]`
+#[proc_macro_derive(UniformBlock, attributes(uniform_block))]
+pub fn derive_uniform_block(item: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(item as DeriveInput);
+
+    match expand_uniform_block(&input) {
+        Ok(tokens) => tokens.into(),
+        Err(e) => e.to_compile_error().into(),
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum BlockLayout {
+    Std140,
+    Std430,
+}
+
+#[derive(Clone, Copy)]
+struct BlockMemberType {
+    glsl: &'static str,
+    msl: &'static str,
+    align: usize,
+    msl_align: usize,
+    size: usize,
+}
+
+fn expand_uniform_block(input: &DeriveInput) -> Result<TokenStream2, Error> {
+    let fields = match &input.data {
+        Data::Struct(s) => match &s.fields {
+            Fields::Named(f) => &f.named,
+            _ => {
+                return Err(Error::new(
+                    input.ident.span(),
+                    "UniformBlock can only be derived for structs with named fields",
+                ))
+            }
+        },
+        _ => {
+            return Err(Error::new(
+                input.ident.span(),
+                "UniformBlock can only be derived for structs",
+            ))
+        }
+    };
+
+    if !input.generics.params.is_empty() {
+        return Err(Error::new(
+            input.generics.span(),
+            "UniformBlock can't be derived for generic structs",
+        ));
+    }
+
+    check_repr_c(input)?;
+
+    let mut layout = BlockLayout::Std140;
+    for attr in input.attrs.iter().filter(|a| a.path().is_ident("uniform_block")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("std140") {
+                layout = BlockLayout::Std140;
+                Ok(())
+            } else if meta.path.is_ident("std430") {
+                layout = BlockLayout::Std430;
+                Ok(())
+            } else {
+                Err(meta.error("Expected `std140` or `std430`"))
+            }
+        })?;
+    }
+
+    let struct_ident = &input.ident;
+    let layout_name = match layout {
+        BlockLayout::Std140 => "std140",
+        BlockLayout::Std430 => "std430",
+    };
+
+    let mut offset = 0;
+    let mut msl_offset = 0;
+    let mut glsl_members = String::new();
+    let mut msl_members = String::new();
+    let mut layout_checks = Vec::new();
+
     for field in fields {
-        for attr in field.attrs {
-            let ident = attr.path().get_ident();
+        let field_ident = field.ident.clone().unwrap();
+        if field_ident.to_string().starts_with('_') {
+            continue;
+        }
+
+        let mut name = field_ident.to_string();
+        for attr in field.attrs.iter().filter(|a| a.path().is_ident("uniform_block")) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("name") {
+                    name = meta.value()?.parse::<syn::LitStr>()?.value();
+                    Ok(())
+                } else {
+                    Err(meta.error("Expected `name = \"...\"`"))
+                }
+            })?;
+        }
 
-            if !ident.is_some_and(|i| i.to_string() == "location") {
-                continue;
+        let (member, array_len) = map_type_to_block_member(&field.ty)?;
+        let (size, array_suffix) = match array_len {
+            Some(len) => {
+                let stride = array_stride(&member, layout);
+                (stride * len, format!("[{len}]"))
             }
+            None => (member.size, String::new()),
+        };
+
+        offset = round_up(offset, member.align);
+
+        msl_offset = round_up(msl_offset, member.msl_align);
+        if msl_offset < offset {
+            msl_members.push_str(&format!(
+                "    char _padding_{name}[{}];\n",
+                offset - msl_offset
+            ));
+        }
+        msl_offset = offset + size;
+
+        glsl_members.push_str(&format!("    {} {name}{array_suffix};\n", member.glsl));
+        msl_members.push_str(&format!("    {} {name}{array_suffix};\n", member.msl));
+
+        let field_type = &field.ty;
+        let message = format!(
+            "`{field_ident}` must be at offset {offset} and take {size} bytes \
+             to match the {layout_name} layout"
+        );
+        layout_checks.push(quote_spanned! {field.span()=>
+            assert!(
+                ::core::mem::size_of::<#field_type>() == #size
+                    && ::core::mem::offset_of!(#struct_ident, #field_ident) == #offset,
+                #message
+            );
+        });
+
+        offset += size;
+    }
 
-            let v = attr
-                .parse_args::<syn::LitInt>()
-                .unwrap()
-                .base10_parse()
-                .unwrap();
-            let type_ident = match field.ty {
-                syn::Type::Path(ref p) => match p.path.get_ident() {
-                    Some(i) => i.to_string(),
-                    None => continue,
+    let layout_variant = Ident::new(
+        match layout {
+            BlockLayout::Std140 => "Std140",
+            BlockLayout::Std430 => "Std430",
+        },
+        Span::call_site(),
+    );
+
+    Ok(quote! {
+        impl UniformBlock for #struct_ident {
+            const LAYOUT: UniformBlockLayout = UniformBlockLayout::#layout_variant;
+            const GLSL_MEMBERS: &'static str = #glsl_members;
+            const MSL_MEMBERS: &'static str = #msl_members;
+        }
+
+        const _: () = {
+            #(#layout_checks)*
+        };
+    })
+}
+
+fn check_repr_c(input: &DeriveInput) -> Result<(), Error> {
+    let mut is_repr_c = false;
+    for attr in input.attrs.iter().filter(|a| a.path().is_ident("repr")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("C") {
+                is_repr_c = true;
+            }
+            if meta.input.peek(syn::token::Paren) {
+                let _content;
+                syn::parenthesized!(_content in meta.input);
+            }
+            Ok(())
+        })?;
+    }
+
+    match is_repr_c {
+        true => Ok(()),
+        false => Err(Error::new(
+            input.ident.span(),
+            "UniformBlock requires `#[repr(C)]` to have a predictable field layout",
+        )),
+    }
+}
+
+fn map_type_to_block_member(
+    ty: &Type,
+) -> Result<(BlockMemberType, Option<usize>), Error> {
+    let unknown_type = || {
+        Error::new(
+            ty.span(),
+            "Unsupported uniform block member type. Expected one of f32, i32, u32, \
+             Vector2, Vector3, Vector4, Matrix4x4, [f32; 2..=4], [i32; 2..=4], \
+             [u32; 2..=4] or an array of them",
+        )
+    };
+
+    let (element, len) = match ty {
+        Type::Path(p) => {
+            let segment = p.path.segments.last().ok_or_else(unknown_type)?;
+            (segment.ident.to_string(), None)
+        }
+        Type::Array(a) => {
+            let len = match &a.len {
+                Expr::Lit(l) => match &l.lit {
+                    Lit::Int(i) => i.base10_parse::<usize>()?,
+                    _ => return Err(unknown_type()),
                 },
-                _ => continue,
+                _ => {
+                    return Err(Error::new(
+                        a.len.span(),
+                        "The array length must be an integer literal",
+                    ))
+                }
             };
 
-            locations.push((v, type_ident));
+            match &*a.elem {
+                Type::Path(p) => {
+                    let segment = p.path.segments.last().ok_or_else(unknown_type)?;
+                    let element = segment.ident.to_string();
+
+                    // Small arrays of scalars are vectors, the rest are shader arrays
+                    match (element.as_str(), len) {
+                        ("f32" | "i32" | "u32", 2..=4) => (element, Some(len)),
+                        _ => {
+                            let (member, _) = map_type_to_block_member(&a.elem)?;
+                            return Ok((member, Some(len)));
+                        }
+                    }
+                }
+                _ => {
+                    let (member, inner_len) = map_type_to_block_member(&a.elem)?;
+                    return match inner_len {
+                        Some(_) => Err(Error::new(
+                            ty.span(),
+                            "Arrays of arrays aren't supported in uniform blocks",
+                        )),
+                        None => Ok((member, Some(len))),
+                    };
+                }
+            }
         }
+        _ => return Err(unknown_type()),
+    };
+
+    let member = |glsl, msl, align, msl_align, size| BlockMemberType {
+        glsl,
+        msl,
+        align,
+        msl_align,
+        size,
+    };
+
+    Ok((
+        match (element.as_str(), len) {
+            ("f32", None) => member("float", "float", 4, 4, 4),
+            ("i32", None) => member("int", "int", 4, 4, 4),
+            ("u32", None) => member("uint", "uint", 4, 4, 4),
+            ("Vector2", None) | ("f32", Some(2)) => member("vec2", "float2", 8, 8, 8),
+            ("Vector3", None) | ("f32", Some(3)) => {
+                member("vec3", "packed_float3", 16, 4, 12)
+            }
+            ("Vector4", None) | ("f32", Some(4)) => member("vec4", "float4", 16, 16, 16),
+            ("Matrix4x4", None) => member("mat4", "float4x4", 16, 16, 64),
+            ("i32", Some(2)) => member("ivec2", "int2", 8, 8, 8),
+            ("i32", Some(3)) => member("ivec3", "packed_int3", 16, 4, 12),
+            ("i32", Some(4)) => member("ivec4", "int4", 16, 16, 16),
+            ("u32", Some(2)) => member("uvec2", "uint2", 8, 8, 8),
+            ("u32", Some(3)) => member("uvec3", "packed_uint3", 16, 4, 12),
+            ("u32", Some(4)) => member("uvec4", "uint4", 16, 16, 16),
+            _ => return Err(unknown_type()),
+        },
+        None,
+    ))
+}
+
+fn array_stride(member: &BlockMemberType, layout: BlockLayout) -> usize {
+    match layout {
+        BlockLayout::Std140 => round_up(member.size, 16),
+        BlockLayout::Std430 => round_up(member.size, member.align),
     }
-    locations
 }
 
-#[proc_macro_attribute]
-pub fn location(_attr: TokenStream, item: TokenStream) -> TokenStream {
-    item
+fn round_up(value: usize, align: usize) -> usize {
+    value.div_ceil(align) * align
 }
@@ -1,10 +1,11 @@
 mod image;
 mod shader;
+mod shader_hot_reload;
 
 use std::{env, path::PathBuf};
 
 pub use image::*;
-pub use shader::*;
+pub use shader_hot_reload::*;
 
 pub fn make_exe_dir_path() -> PathBuf {
     env::current_exe().unwrap().parent().unwrap().to_path_buf()
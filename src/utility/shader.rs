@@ -1,59 +1,108 @@
-use std::{env, fs, io, path::Path};
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+};
 
-use crate::core::{Context, DivisionId, context::Error, ShaderSourceDescriptor, ShaderType};
+use crate::core::{
+    Context, context::Error, ShaderPreprocessor, ShaderProgram, ShaderSourceDescriptor,
+    ShaderType,
+};
 
 impl Context {
     /// Creates program with vertex and fragment bundled shaders with same names.
     /// [`shader_path`] should be relative to the executable folder.
+    /// GLSL sources go through `ShaderPreprocessor` without defines.
     /// Not suitable for complex cases
     pub fn create_bundled_shader_program(
         &mut self,
         shader_path: &Path,
-    ) -> Result<DivisionId, Error> {
-        let shader_path = shader_path
-            .to_str()
-            .ok_or_else(|| Error::Core("The shader path is incorrect".to_string()))?;
-
-        let (vert_entry, vert_path, frag_entry, frag_path) = if cfg!(target_os = "macos")
-        {
-            (
-                "vert",
-                format!("{shader_path}.vert.metal"),
-                "frag",
-                format!("{shader_path}.frag.metal"),
-            )
-        } else {
-            (
-                "main",
-                format!("{shader_path}.vert"),
-                "main",
-                format!("{shader_path}.frag"),
-            )
-        };
-
+    ) -> Result<ShaderProgram, Error> {
         let bin_root_path = env::current_exe().unwrap();
         let bin_root_path = bin_root_path.parent().unwrap();
 
-        self.create_shader_program(&[
-            ShaderSourceDescriptor::new(
-                ShaderType::Vertex,
-                vert_entry,
-                &fs::read_to_string(bin_root_path.join(vert_path))
-                    .map_err(|e| new_shader_read_error("vertex", e))?,
-            ),
-            ShaderSourceDescriptor::new(
-                ShaderType::Fragment,
-                frag_entry,
-                &fs::read_to_string(bin_root_path.join(frag_path))
-                    .map_err(|e| new_shader_read_error("fragment", e))?,
-            ),
-        ])
+        let files = bundled_shader_files(bin_root_path, shader_path)?;
+        self.create_shader_program(&read_shader_sources(&files)?)
     }
 }
 
-fn new_shader_read_error(shader_type: &str, base_error: io::Error) -> Error {
-    let io_message = base_error.to_string();
-    Error::Core(format!(
-        "Failed to read {shader_type} shader. io::Error message: `{io_message}`"
-    ))
+pub(super) struct BundledShaderFile {
+    pub shader_type: ShaderType,
+    pub entry_point: &'static str,
+    pub path: PathBuf,
+}
+
+/// Files `create_bundled_shader_program` reads, `.metal` ones on macOS
+pub(super) fn bundled_shader_files(
+    root_path: &Path,
+    shader_path: &Path,
+) -> Result<[BundledShaderFile; 2], Error> {
+    let extension = if cfg!(target_os = "macos") { ".metal" } else { "" };
+    shader_files(root_path, shader_path, extension)
+}
+
+/// GLSL sources for `create_shader_program_from_glsl` on every backend
+pub(super) fn glsl_shader_files(
+    root_path: &Path,
+    shader_path: &Path,
+) -> Result<[BundledShaderFile; 2], Error> {
+    shader_files(root_path, shader_path, "")
+}
+
+fn shader_files(
+    root_path: &Path,
+    shader_path: &Path,
+    extension: &str,
+) -> Result<[BundledShaderFile; 2], Error> {
+    let shader_path = shader_path
+        .to_str()
+        .ok_or_else(|| Error::Core("The shader path is incorrect".to_string()))?;
+
+    let (vert_entry, frag_entry) = if cfg!(target_os = "macos") {
+        ("vert", "frag")
+    } else {
+        ("main", "main")
+    };
+
+    Ok([
+        BundledShaderFile {
+            shader_type: ShaderType::Vertex,
+            entry_point: vert_entry,
+            path: root_path.join(format!("{shader_path}.vert{extension}")),
+        },
+        BundledShaderFile {
+            shader_type: ShaderType::Fragment,
+            entry_point: frag_entry,
+            path: root_path.join(format!("{shader_path}.frag{extension}")),
+        },
+    ])
+}
+
+/// GLSL sources are preprocessed. The `.metal` ones are generated from
+/// the preprocessed GLSL by the build script, so they are passed as is
+pub(super) fn read_shader_sources(
+    files: &[BundledShaderFile],
+) -> Result<Vec<ShaderSourceDescriptor>, Error> {
+    let preprocessor = ShaderPreprocessor::new();
+    files
+        .iter()
+        .map(|f| {
+            if f.path.extension().is_some_and(|e| e == "metal") {
+                let source = fs::read_to_string(&f.path).map_err(|e| {
+                    Error::Core(format!("Failed to read `{}`: {e}", f.path.display()))
+                })?;
+                return Ok(ShaderSourceDescriptor::new(
+                    f.shader_type,
+                    f.entry_point,
+                    &source,
+                ));
+            }
+
+            ShaderSourceDescriptor::from_file(
+                f.shader_type,
+                f.entry_point,
+                &f.path,
+                &preprocessor,
+            )
+        })
+        .collect()
 }
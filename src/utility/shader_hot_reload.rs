@@ -0,0 +1,258 @@
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+    time::{Duration, Instant, SystemTime},
+};
+
+use crate::core::{
+    context::Error, Context, DivisionId, ShaderProgram, ShaderSourceDescriptor,
+};
+
+use super::shader::{glsl_shader_files, read_shader_sources, BundledShaderFile};
+
+/// Development helper that reloads bundled shader programs when their
+/// sources change.
+///
+/// Programs are loaded with the same file layout as
+/// `Context::create_bundled_shader_program`, but relative to `root_dir`,
+/// so it can point to the source `resources` instead of the copies next to
+/// the executable. GLSL sources are used on every backend and compiled with
+/// `Context::create_shader_program_from_glsl`.
+///
+/// The reloader owns the programs it loaded, they are swapped into the attached
+/// render pass descriptors and deleted with `delete`. A replaced program may still
+/// be used by the passes submitted in the current frame, so it's deleted
+/// on the next `poll`
+pub struct ShaderHotReloader {
+    root_dir: PathBuf,
+    poll_interval: Duration,
+    last_poll: Option<Instant>,
+    programs: Vec<WatchedProgram>,
+    replaced_programs: Vec<DivisionId>,
+}
+
+/// Handle of a program loaded by `ShaderHotReloader`.
+/// The program id changes on every reload, use `ShaderHotReloader::shader_id`
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct HotShader(usize);
+
+struct WatchedProgram {
+    program: ShaderProgram,
+    files: [BundledShaderFile; 2],
+    watched_files: Vec<(PathBuf, Option<SystemTime>)>,
+    render_pass_descriptors: Vec<DivisionId>,
+}
+
+impl ShaderHotReloader {
+    pub const DEFAULT_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+    /// Watches the files next to the executable, as `create_bundled_shader_program`.
+    /// Fails if the executable path can't be resolved
+    pub fn new() -> Result<ShaderHotReloader, Error> {
+        let exe_path = env::current_exe().map_err(|e| {
+            Error::Core(format!("Failed to get the executable path: {e}"))
+        })?;
+        let root_dir = exe_path.parent().ok_or_else(|| {
+            Error::Core(format!(
+                "The executable path `{}` has no parent directory",
+                exe_path.display()
+            ))
+        })?;
+
+        Ok(ShaderHotReloader {
+            root_dir: root_dir.to_path_buf(),
+            poll_interval: Self::DEFAULT_POLL_INTERVAL,
+            last_poll: None,
+            programs: Vec::new(),
+            replaced_programs: Vec::new(),
+        })
+    }
+
+    pub fn root_dir(mut self, root_dir: &Path) -> Self {
+        self.root_dir = root_dir.to_path_buf();
+        self
+    }
+
+    pub fn poll_interval(mut self, poll_interval: Duration) -> Self {
+        self.poll_interval = poll_interval;
+        self
+    }
+
+    pub fn load(
+        &mut self,
+        context: &mut Context,
+        shader_path: &Path,
+    ) -> Result<HotShader, Error> {
+        let files = glsl_shader_files(&self.root_dir, shader_path)?;
+        let descriptors = read_shader_sources(&files)?;
+        let program = context.create_shader_program_from_glsl(&descriptors)?;
+
+        self.programs.push(WatchedProgram {
+            program,
+            files,
+            watched_files: watched_files(&descriptors),
+            render_pass_descriptors: Vec::new(),
+        });
+
+        Ok(HotShader(self.programs.len() - 1))
+    }
+
+    #[inline]
+    pub fn shader_id(&self, shader: HotShader) -> DivisionId {
+        self.programs[shader.0].program.id
+    }
+
+    #[inline]
+    pub fn shader_program(&self, shader: HotShader) -> &ShaderProgram {
+        &self.programs[shader.0].program
+    }
+
+    /// The descriptor gets the new program every time the shader is reloaded
+    pub fn attach_render_pass(
+        &mut self,
+        shader: HotShader,
+        render_pass_descriptor_id: DivisionId,
+    ) {
+        self.programs[shader.0]
+            .render_pass_descriptors
+            .push(render_pass_descriptor_id);
+    }
+
+    /// Attaches many descriptors, e.g. `RectRenderer::render_pass_descriptors`
+    pub fn attach_render_passes(
+        &mut self,
+        shader: HotShader,
+        render_pass_descriptor_ids: impl IntoIterator<Item = DivisionId>,
+    ) {
+        self.programs[shader.0]
+            .render_pass_descriptors
+            .extend(render_pass_descriptor_ids);
+    }
+
+    pub fn detach_render_pass(
+        &mut self,
+        shader: HotShader,
+        render_pass_descriptor_id: DivisionId,
+    ) {
+        self.programs[shader.0]
+            .render_pass_descriptors
+            .retain(|&id| id != render_pass_descriptor_id);
+    }
+
+    /// Checks the sources at most once per poll interval and reloads the changed
+    /// programs. Returns the changed shaders with the result of their reload.
+    /// The programs replaced by the previous poll are deleted first.
+    ///
+    /// If a program fails to compile, or doesn't fit the vertex buffer of
+    /// an attached render pass, the error is returned and the old program is kept
+    pub fn poll(
+        &mut self,
+        context: &mut Context,
+    ) -> Vec<(HotShader, Result<(), Error>)> {
+        self.delete_replaced_programs(context);
+
+        let now = Instant::now();
+        if let Some(last_poll) = self.last_poll {
+            if now.duration_since(last_poll) < self.poll_interval {
+                return Vec::new();
+            }
+        }
+        self.last_poll = Some(now);
+
+        let mut reloaded = Vec::new();
+        for (index, watched) in self.programs.iter_mut().enumerate() {
+            let is_changed = watched
+                .watched_files
+                .iter()
+                .any(|(path, modified)| modified_time(path) != *modified);
+            if !is_changed {
+                continue;
+            }
+
+            let result = watched.reload(context, &mut self.replaced_programs);
+            reloaded.push((HotShader(index), result));
+        }
+
+        reloaded
+    }
+
+    /// Deletes all loaded programs
+    pub fn delete(&mut self, context: &mut Context) {
+        self.delete_replaced_programs(context);
+        for watched in self.programs.drain(..) {
+            context.delete_shader_program(watched.program.id);
+        }
+    }
+
+    fn delete_replaced_programs(&mut self, context: &mut Context) {
+        for program_id in self.replaced_programs.drain(..) {
+            context.delete_shader_program(program_id);
+        }
+    }
+}
+
+impl WatchedProgram {
+    fn reload(
+        &mut self,
+        context: &mut Context,
+        replaced_programs: &mut Vec<DivisionId>,
+    ) -> Result<(), Error> {
+        let descriptors = match read_shader_sources(&self.files) {
+            Ok(descriptors) => descriptors,
+            Err(e) => {
+                for (path, modified) in &mut self.watched_files {
+                    *modified = modified_time(path);
+                }
+                return Err(e);
+            }
+        };
+        self.watched_files = watched_files(&descriptors);
+
+        let program = context.create_shader_program_from_glsl(&descriptors)?;
+
+        for &render_pass_id in &self.render_pass_descriptors {
+            let vertex_buffer_id = context
+                .borrow_render_pass_descriptor(render_pass_id)
+                .vertex_buffer_id;
+            let validation = context
+                .vertex_buffer_attributes(vertex_buffer_id)
+                .and_then(|(per_vertex, per_instance)| {
+                    program
+                        .reflection
+                        .validate_vertex_attributes(&per_vertex, &per_instance)
+                        .map_err(Error::ShaderValidation)
+                });
+
+            if let Err(e) = validation {
+                context.delete_shader_program(program.id);
+                return Err(e);
+            }
+        }
+
+        for &render_pass_id in &self.render_pass_descriptors {
+            context
+                .borrow_render_pass_descriptor_mut(render_pass_id)
+                .shader_program = program.id;
+        }
+
+        replaced_programs.push(self.program.id);
+        self.program = program;
+
+        Ok(())
+    }
+}
+
+/// Sources with all their includes
+fn watched_files(
+    descriptors: &[ShaderSourceDescriptor],
+) -> Vec<(PathBuf, Option<SystemTime>)> {
+    descriptors
+        .iter()
+        .flat_map(|d| d.source_files())
+        .map(|path| (path.clone(), modified_time(path)))
+        .collect()
+}
+
+fn modified_time(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|m| m.modified()).ok()
+}